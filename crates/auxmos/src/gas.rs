@@ -3,6 +3,8 @@ pub mod constants;
 
 pub mod mixture;
 
+pub mod stats;
+
 pub mod types;
 
 use auxtools::*;
@@ -11,11 +13,15 @@ pub use types::*;
 
 use fxhash::FxBuildHasher;
 
-use parking_lot::{const_rwlock, RwLock};
+use parking_lot::{const_rwlock, RwLock, RwLockReadGuard};
 
 pub use mixture::Mixture;
 
-use std::{cell::RefCell, collections::HashSet};
+use std::{
+	cell::{Cell, RefCell},
+	collections::HashSet,
+	sync::atomic::{AtomicUsize, Ordering},
+};
 
 pub type GasIDX = usize;
 
@@ -33,8 +39,15 @@ static GAS_MIXTURES: RwLock<Option<Vec<RwLock<Mixture>>>> = const_rwlock(None);
 
 static NEXT_GAS_IDS: RwLock<Option<Vec<usize>>> = const_rwlock(None);
 
+/// The most gas mixtures that have been in use at once, for leak hunting.
+static GAS_HIGH_WATER_MARK: AtomicUsize = AtomicUsize::new(0);
+
 thread_local! {
 	static REGISTERED_GAS_MIXES: RefCell<Option<HashSet<u32, FxBuildHasher>>> = RefCell::new(None);
+	// Whether this is the main thread, the only one that can read gas mixtures without their locks, see with_gas_mixture.
+	static IS_MAIN_THREAD: Cell<bool> = Cell::new(false);
+	// The gas mixture the main thread is reading without its lock right now, if any.
+	static UNLOCKED_READ: Cell<Option<usize>> = Cell::new(None);
 }
 
 //is registered mix may be called when byond's del datum runs after world shutdown is done.
@@ -70,6 +83,7 @@ fn _initialize_gas_mixtures() -> Result<(), String> {
 	*GAS_MIXTURES.write() = Some(Vec::with_capacity(240_000));
 	*NEXT_GAS_IDS.write() = Some(Vec::with_capacity(2000));
 	REGISTERED_GAS_MIXES.with(|thing| *thing.borrow_mut() = Some(Default::default()));
+	IS_MAIN_THREAD.with(|main| main.set(true));
 	Ok(())
 }
 
@@ -78,7 +92,72 @@ fn _shut_down_gases() {
 	crate::turfs::wait_for_tasks();
 	GAS_MIXTURES.write().as_mut().unwrap().clear();
 	NEXT_GAS_IDS.write().as_mut().unwrap().clear();
+	GAS_HIGH_WATER_MARK.store(0, Ordering::Relaxed);
 	REGISTERED_GAS_MIXES.with(|thing| *thing.borrow_mut() = None);
+	IS_MAIN_THREAD.with(|main| main.set(false));
+}
+
+// How many worker phases are alive, see WorkerPhase.
+static ACTIVE_WORKER_PHASES: AtomicUsize = AtomicUsize::new(0);
+
+/// While one of these is alive, worker threads may be touching gas mixtures, so the main thread takes every mixture's lock.
+/// Begin it on the main thread, or while another phase is alive, and move it to the workers, dropping it once they're done;
+/// that way there's never a moment where workers are running and the main thread thinks they're idle.
+pub struct WorkerPhase(());
+
+impl WorkerPhase {
+	#[must_use]
+	pub fn begin() -> Self {
+		ACTIVE_WORKER_PHASES.fetch_add(1, Ordering::SeqCst);
+		Self(())
+	}
+}
+
+impl Drop for WorkerPhase {
+	fn drop(&mut self) {
+		ACTIVE_WORKER_PHASES.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
+/// Whether no worker phase is alive, so no other thread can be touching gas mixtures.
+pub fn workers_idle() -> bool {
+	ACTIVE_WORKER_PHASES.load(Ordering::SeqCst) == 0
+}
+
+/// Whether a read of the given gas mixture from this thread, right now, would skip the mixture's own lock.
+/// Only ever true on the main thread while workers are idle, outside of another lock-free read,
+/// and if nothing on this thread has the mixture locked already.
+fn can_skip_mix_lock(mix: &RwLock<Mixture>) -> bool {
+	IS_MAIN_THREAD.with(Cell::get)
+		&& UNLOCKED_READ.with(Cell::get).is_none()
+		&& workers_idle()
+		&& !mix.is_locked()
+}
+
+// Read locks the mixture the main thread is reading without its lock, if there is one, for as long as the guard lives.
+// Everything that can hand out write access takes this first, so writing to that mixture from inside the read blocks,
+// just like it would have if the read had taken the lock, instead of aliasing it.
+fn lock_unlocked_read(gas_mixtures: &[RwLock<Mixture>]) -> Option<RwLockReadGuard<'_, Mixture>> {
+	UNLOCKED_READ
+		.with(Cell::get)
+		.and_then(|id| gas_mixtures.get(id))
+		.map(RwLock::read)
+}
+
+// Marks a lock-free read as over when dropped, even if the read panics.
+struct UnlockedRead;
+
+impl UnlockedRead {
+	fn begin(id: usize) -> Self {
+		UNLOCKED_READ.with(|read| read.set(Some(id)));
+		Self
+	}
+}
+
+impl Drop for UnlockedRead {
+	fn drop(&mut self) {
+		UNLOCKED_READ.with(|read| read.set(None));
+	}
 }
 
 impl GasArena {
@@ -89,9 +168,21 @@ impl GasArena {
 	where
 		F: FnOnce(&[RwLock<Mixture>]) -> T,
 	{
-		f(GAS_MIXTURES.read().as_ref().unwrap())
+		Self::try_with_all_mixtures(f).unwrap()
+	}
+	/// As `with_all_mixtures`, but returns `None` instead of panicking if the arena isn't initialized,
+	/// e.g. before init or after world shutdown.
+	pub fn try_with_all_mixtures<T, F>(f: F) -> Option<T>
+	where
+		F: FnOnce(&[RwLock<Mixture>]) -> T,
+	{
+		GAS_MIXTURES.read().as_deref().map(|gas_mixtures| {
+			let _unlocked_read = lock_unlocked_read(gas_mixtures);
+			f(gas_mixtures)
+		})
 	}
 	/// Read locks the given gas mixture and runs the given closure on it.
+	/// On the main thread, while no `WorkerPhase` is alive, only the arena is locked and the mixture's own lock is skipped.
 	/// # Errors
 	/// If no such gas mixture exists or the closure itself errors.
 	/// # Panics
@@ -104,9 +195,24 @@ impl GasArena {
 		let gas_mixtures = lock.as_ref().unwrap();
 		let mix = gas_mixtures
 			.get(id)
-			.ok_or_else(|| runtime!("No gas mixture with ID {} exists!", id))?
-			.read();
-		f(&mix)
+			.ok_or_else(|| runtime!("No gas mixture with ID {} exists!", id))?;
+		if can_skip_mix_lock(mix) {
+			let _unlocked_read = UnlockedRead::begin(id);
+			// Safety: no other thread can touch gas mixtures while workers are idle, and phases can only begin
+			// on this thread, which is busy here, or while another phase is alive.
+			// Nothing on this thread holds the mixture's lock, and until the read's over, everything here that could
+			// write to it read locks it first, see lock_unlocked_read, so there's no mutable reference to it while this one lives.
+			return f(unsafe { &*mix.data_ptr() });
+		}
+		f(&mix.read())
+	}
+	/// Whether reading the given gas mixture from this thread right now would skip the mixture's own lock, see `with_gas_mixture`.
+	pub fn read_skips_mix_lock(id: usize) -> bool {
+		GAS_MIXTURES
+			.read()
+			.as_ref()
+			.and_then(|gas_mixtures| gas_mixtures.get(id))
+			.map_or(false, can_skip_mix_lock)
 	}
 	/// Write locks the given gas mixture and runs the given closure on it.
 	/// # Errors
@@ -119,6 +225,7 @@ impl GasArena {
 	{
 		let lock = GAS_MIXTURES.read();
 		let gas_mixtures = lock.as_ref().unwrap();
+		let _unlocked_read = lock_unlocked_read(gas_mixtures);
 		let mut mix = gas_mixtures
 			.get(id)
 			.ok_or_else(|| runtime!("No gas mixture with ID {} exists!", id))?
@@ -159,6 +266,7 @@ impl GasArena {
 		let arg = arg;
 		let lock = GAS_MIXTURES.read();
 		let gas_mixtures = lock.as_ref().unwrap();
+		let _unlocked_read = lock_unlocked_read(gas_mixtures);
 		if src == arg {
 			let mut entry = gas_mixtures
 				.get(src)
@@ -180,6 +288,72 @@ impl GasArena {
 			)
 		}
 	}
+	/// Write locks all of the given gas mixtures and runs the given closure on them, in the same order as the IDs given.
+	/// Locks are always acquired in ascending ID order, so concurrent callers can't deadlock each other.
+	/// # Errors
+	/// If any gas mixture doesn't exist, the same ID is given twice, or the closure itself errors.
+	/// # Panics
+	/// if `GAS_MIXTURES` hasn't been initialized, somehow.
+	pub fn with_gas_mixtures_many_mut<T, F>(ids: &[usize], f: F) -> Result<T, Runtime>
+	where
+		F: FnOnce(&mut [&mut Mixture]) -> Result<T, Runtime>,
+	{
+		let lock = GAS_MIXTURES.read();
+		let gas_mixtures = lock.as_ref().unwrap();
+		let _unlocked_read = lock_unlocked_read(gas_mixtures);
+		let mut lock_order = (0..ids.len()).collect::<Vec<_>>();
+		lock_order.sort_unstable_by_key(|&i| ids[i]);
+		if lock_order
+			.windows(2)
+			.any(|pair| ids[pair[0]] == ids[pair[1]])
+		{
+			return Err(runtime!("Attempted to lock the same gas mixture twice!"));
+		}
+		let mut guards = lock_order
+			.into_iter()
+			.map(|i| {
+				Ok((
+					i,
+					gas_mixtures
+						.get(ids[i])
+						.ok_or_else(|| runtime!("No gas mixture with ID {} exists!", ids[i]))?
+						.write(),
+				))
+			})
+			.collect::<Result<Vec<_>, Runtime>>()?;
+		guards.sort_unstable_by_key(|&(i, _)| i);
+		let mut mixes = guards
+			.iter_mut()
+			.map(|(_, guard)| &mut **guard)
+			.collect::<Vec<_>>();
+		f(&mut mixes)
+	}
+	/// Moves gas between many pairs of gas mixtures at once, each op being (from, to, ratio of from's gas to move).
+	/// The ops are done in order, with every mixture involved locked the whole time, in ascending ID order.
+	/// Ops moving a mixture into itself do nothing.
+	/// # Errors
+	/// If any gas mixture doesn't exist.
+	pub fn transfer_batch(ops: &[(usize, usize, f32)]) -> Result<(), Runtime> {
+		let mut ids = ops
+			.iter()
+			.flat_map(|&(from, to, _)| [from, to])
+			.collect::<Vec<_>>();
+		ids.sort_unstable();
+		ids.dedup();
+		Self::with_gas_mixtures_many_mut(&ids, |mixes| {
+			for &(from, to, ratio) in ops {
+				if from == to {
+					continue;
+				}
+				// both are in there, since ids was made from the ops
+				let from_idx = ids.binary_search(&from).unwrap();
+				let to_idx = ids.binary_search(&to).unwrap();
+				let removed = mixes[from_idx].remove_ratio(ratio);
+				mixes[to_idx].merge(&removed);
+			}
+			Ok(())
+		})
+	}
 	/// Runs the given closure on the gas mixture *locks* rather than an already-locked version.
 	/// # Errors
 	/// If no such gas mixture exists or the closure itself errors.
@@ -193,6 +367,7 @@ impl GasArena {
 		let arg = arg;
 		let lock = GAS_MIXTURES.read();
 		let gas_mixtures = lock.as_ref().unwrap();
+		let _unlocked_read = lock_unlocked_read(gas_mixtures);
 		if src == arg {
 			let entry = gas_mixtures
 				.get(src)
@@ -241,30 +416,33 @@ impl GasArena {
 				let mut next_gas_ids = NEXT_GAS_IDS.write();
 				next_gas_ids.as_mut().unwrap().pop().unwrap()
 			};
-			GAS_MIXTURES
-				.read()
-				.as_ref()
-				.unwrap()
-				.get(idx)
-				.unwrap()
-				.write()
-				.clear_with_vol(
-					mix.get_number(byond_string!("initial_volume"))
-						.map_err(|_| {
-							runtime!(
-								"Attempt to interpret non-number value as number {} {}:{}",
-								std::file!(),
-								std::line!(),
-								std::column!()
-							)
-						})?,
-				);
+			let volume = mix
+				.get_number(byond_string!("initial_volume"))
+				.map_err(|_| {
+					runtime!(
+						"Attempt to interpret non-number value as number {} {}:{}",
+						std::file!(),
+						std::line!(),
+						std::column!()
+					)
+				})?;
+			{
+				let lock = GAS_MIXTURES.read();
+				let gas_mixtures = lock.as_ref().unwrap();
+				let _unlocked_read = lock_unlocked_read(gas_mixtures);
+				gas_mixtures
+					.get(idx)
+					.unwrap()
+					.write()
+					.clear_with_vol(volume);
+			}
 			mix.set(
 				byond_string!("_extools_pointer_gasmixture"),
 				f32::from_bits(idx as u32),
 			)?;
 		}
 		register_mix(mix);
+		GAS_HIGH_WATER_MARK.fetch_max(amt_gases(), Ordering::Relaxed);
 		rayon::spawn(|| {
 			if NEXT_GAS_IDS.read().as_ref().unwrap().is_empty() {
 				let mut gas_lock = GAS_MIXTURES.write();
@@ -318,6 +496,280 @@ impl GasArena {
 			}
 		}
 	}
+	/// Returns the amount of gas mixtures in use, the amount of free slots, and the amount of slots allocated, in that order.
+	/// # Panics
+	/// If `GAS_MIXTURES` or `NEXT_GAS_IDS` haven't been initialized, somehow.
+	pub fn occupancy_stats() -> (usize, usize, usize) {
+		let gas_lock = GAS_MIXTURES.read();
+		let gas_mixtures = gas_lock.as_ref().unwrap();
+		let free = NEXT_GAS_IDS.read().as_ref().unwrap().len();
+		(gas_mixtures.len() - free, free, gas_mixtures.capacity())
+	}
+	/// Returns the most gas mixtures that have been in use at once since initialization.
+	pub fn high_water_mark() -> usize {
+		GAS_HIGH_WATER_MARK.load(Ordering::Relaxed)
+	}
+	/// Sums up the thermal energy of every gas mixture in use, in f64 so precision isn't lost over hundreds of thousands of mixtures.
+	/// # Panics
+	/// If `GAS_MIXTURES` or `NEXT_GAS_IDS` haven't been initialized, somehow.
+	pub fn total_thermal_energy() -> f64 {
+		let lock = GAS_MIXTURES.read();
+		let gas_mixtures = lock.as_ref().unwrap();
+		gas_mixtures
+			.iter()
+			.zip(Self::in_use_mask(gas_mixtures.len()))
+			.filter(|&(_, used)| used)
+			.map(|(mix, _)| f64::from(mix.read().thermal_energy()))
+			.sum()
+	}
+	/// The heat-capacity-weighted average temperature of the given gas mixtures, read all at once.
+	/// Mixtures without heat capacity, and ids that don't exist, are left out. If nothing's left, returns `TCMB`.
+	/// # Panics
+	/// If `GAS_MIXTURES` hasn't been initialized, somehow.
+	pub fn average_temperature(ids: &[usize]) -> f32 {
+		let lock = GAS_MIXTURES.read();
+		let gas_mixtures = lock.as_ref().unwrap();
+		let (energy, capacity) = ids
+			.iter()
+			.filter_map(|&id| gas_mixtures.get(id))
+			.map(|mix| {
+				let mix = mix.read();
+				(mix.heat_capacity(), mix.get_temperature())
+			})
+			.filter(|&(cap, _)| cap >= constants::MINIMUM_HEAT_CAPACITY)
+			.fold((0.0_f64, 0.0_f64), |(energy, capacity), (cap, temp)| {
+				(
+					energy + f64::from(cap) * f64::from(temp),
+					capacity + f64::from(cap),
+				)
+			});
+		if capacity > 0.0 {
+			(energy / capacity) as f32
+		} else {
+			constants::TCMB
+		}
+	}
+	/// The pressures of the given gas mixtures, in the same order, read all at once.
+	/// Empty mixtures, and ids that don't exist, read as 0.
+	/// # Panics
+	/// If `GAS_MIXTURES` hasn't been initialized, somehow.
+	pub fn pressures_for(ids: &[usize]) -> Vec<f32> {
+		let lock = GAS_MIXTURES.read();
+		let gas_mixtures = lock.as_ref().unwrap();
+		let floors = mixture::sim_floors();
+		ids.iter()
+			.map(|&id| {
+				gas_mixtures.get(id).map_or(0.0, |mix| {
+					let mix = mix.read();
+					if mix.is_empty_with_floors(floors) {
+						0.0
+					} else {
+						mix.return_pressure()
+					}
+				})
+			})
+			.collect()
+	}
+	/// The total moles of each gas across the given gas mixtures, read all at once and summed in f64.
+	/// Gases none of them have, and ids that don't exist, are left out.
+	/// # Panics
+	/// If `GAS_MIXTURES` hasn't been initialized, somehow.
+	pub fn sum_gases(ids: &[usize]) -> Vec<(GasIDX, f64)> {
+		let lock = GAS_MIXTURES.read();
+		let gas_mixtures = lock.as_ref().unwrap();
+		let mut totals: Vec<f64> = Vec::new();
+		for mix in ids.iter().filter_map(|&id| gas_mixtures.get(id)) {
+			let mix = mix.read();
+			for (idx, amt) in mix.enumerate() {
+				if totals.len() <= idx {
+					totals.resize(idx + 1, 0.0);
+				}
+				totals[idx] += f64::from(amt);
+			}
+		}
+		totals
+			.into_iter()
+			.enumerate()
+			.filter(|&(_, total)| total > 0.0)
+			.collect()
+	}
+	/// Serializes the given gas mixtures, in parallel, into one blob, for persistence.
+	/// Layout, little-endian: version byte, a gas table as in `Mixture::to_bytes` for the whole registry,
+	/// the amount of mixtures, then each mixture as a length-prefixed `Mixture::to_bytes_untabled`.
+	/// Empty mixtures, and ids that aren't in use, are written as a length of 0.
+	/// # Panics
+	/// If `GAS_MIXTURES` or `NEXT_GAS_IDS` haven't been initialized, somehow.
+	pub fn serialize_all(ids: &[usize]) -> Vec<u8> {
+		use rayon::prelude::*;
+		let bodies = {
+			let lock = GAS_MIXTURES.read();
+			let gas_mixtures = lock.as_ref().unwrap();
+			let in_use = Self::in_use_mask(gas_mixtures.len());
+			let floors = mixture::sim_floors();
+			ids.par_iter()
+				.map(|&id| {
+					gas_mixtures
+						.get(id)
+						.filter(|_| in_use[id])
+						.map(|mix| mix.read())
+						.filter(|mix| !mix.is_empty_with_floors(floors))
+						.map_or_else(Vec::new, |mix| mix.to_bytes_untabled())
+				})
+				.collect::<Vec<_>>()
+		};
+		let mut bytes =
+			Vec::with_capacity(5 + bodies.iter().map(|body| body.len() + 4).sum::<usize>());
+		bytes.push(constants::ARENA_SERIALIZATION_VERSION);
+		mixture::write_gas_table(0..total_num_gases(), &mut bytes);
+		bytes.extend_from_slice(&(bodies.len() as u32).to_le_bytes());
+		for body in bodies {
+			bytes.extend_from_slice(&(body.len() as u32).to_le_bytes());
+			bytes.extend_from_slice(&body);
+		}
+		bytes
+	}
+	/// Deserializes a blob made by `serialize_all`, in the same order. Empty entries read as `None`.
+	/// Gases that no longer exist are dropped.
+	/// # Errors
+	/// If the blob is truncated, from an unknown version, or any mixture in it is malformed.
+	pub fn deserialize_all(bytes: &[u8]) -> Result<Vec<Option<Mixture>>, Runtime> {
+		let mut reader = bytes;
+		let version = mixture::take_bytes(&mut reader, 1)?[0];
+		if version != constants::ARENA_SERIALIZATION_VERSION {
+			return Err(runtime!(
+				"Unknown serialized gas mixture blob version: {}",
+				version
+			));
+		}
+		let remap = mixture::read_gas_table(&mut reader)?;
+		let count = mixture::take_u32(&mut reader)?;
+		let mut mixes = Vec::with_capacity((count as usize).min(reader.len() / 4));
+		for _ in 0..count {
+			let len = mixture::take_u32(&mut reader)? as usize;
+			if len == 0 {
+				mixes.push(None);
+				continue;
+			}
+			let mut body = mixture::take_bytes(&mut reader, len)?;
+			mixes.push(Some(Mixture::from_bytes_untabled(&mut body, &remap)?));
+		}
+		Ok(mixes)
+	}
+	/// Returns whether each gas mixture slot, up to `len`, is in use rather than free.
+	/// Must be called with `GAS_MIXTURES` locked, so the slots don't change underneath.
+	/// # Panics
+	/// If `NEXT_GAS_IDS` hasn't been initialized, somehow.
+	fn in_use_mask(len: usize) -> Vec<bool> {
+		let mut in_use = vec![true; len];
+		for &id in NEXT_GAS_IDS.read().as_ref().unwrap() {
+			if let Some(used) = in_use.get_mut(id) {
+				*used = false;
+			}
+		}
+		in_use
+	}
+	/// Reacts every gas mixture in use that can react, running the reactions hooked in Rust in parallel.
+	/// Hooked reactions only ever touch their own mix, so they're safe to run on the thread pool under each mix's write lock.
+	/// Reactions defined in DM, and everything hooked reactions do outside their mix, like exposing the holder to fire,
+	/// call into byond, so they're queued up and run on this thread afterwards, which must be the main thread.
+	/// A mix's reactions still run in priority order: once it gets to one defined in DM, that one and every one after it wait for this thread.
+	/// `holder_resolver` gives the datum and holder of the gas mixture with the given index.
+	/// Gas mixtures it gives `None` for are skipped entirely, since their reactions would have nothing to expose.
+	/// Returns the amount of gas mixtures that reacted.
+	/// # Errors
+	/// If any reaction has a runtime. Every other gas mixture is still reacted.
+	/// # Panics
+	/// If `GAS_MIXTURES` or `NEXT_GAS_IDS` haven't been initialized, somehow.
+	#[cfg_attr(not(feature = "turf_processing"), allow(dead_code))]
+	pub fn parallel_react_all(
+		holder_resolver: impl Fn(usize) -> Option<(Value, Value)>,
+	) -> Result<usize, Runtime> {
+		use rayon::prelude::*;
+		let reactable = with_reactions(|reactions| {
+			let lock = GAS_MIXTURES.read();
+			let gas_mixtures = lock.as_ref().unwrap();
+			gas_mixtures
+				.par_iter()
+				.zip(Self::in_use_mask(gas_mixtures.len()))
+				.enumerate()
+				.filter_map(|(idx, (mix, used))| {
+					(used && mix.read().can_react_with_reactions(reactions)).then_some(idx)
+				})
+				.collect::<Vec<_>>()
+		});
+		// values can't leave this thread, so they're kept apart from the indices the pool gets
+		let (indices, holders): (Vec<_>, Vec<_>) = reactable
+			.into_iter()
+			.filter_map(|idx| Some((idx, holder_resolver(idx)?)))
+			.unzip();
+		let hooked = crate::reaction::hooked_reactions();
+		let reactions = {
+			let _phase = WorkerPhase::begin();
+			let lock = GAS_MIXTURES.read();
+			let gas_mixtures = lock.as_ref().unwrap();
+			indices
+				.par_iter()
+				.map(|&idx| {
+					gas_mixtures
+						.get(idx)
+						.map(|mix| hooked.react(&mut mix.write()))
+				})
+				.collect::<Vec<_>>()
+		};
+		let mut reacted = 0;
+		let mut first_error = None;
+		for (reaction, (src, holder)) in reactions.into_iter().zip(holders) {
+			match reaction
+				.map(|reaction| reaction.and_then(|reaction| reaction.finish(&src, &holder)))
+			{
+				Some(Ok(true)) => reacted += 1,
+				Some(Err(error)) => {
+					first_error.get_or_insert(error);
+				}
+				_ => (),
+			}
+		}
+		first_error.map_or(Ok(reacted), Err)
+	}
+	/// Removes every unused gas mixture at the end of the arena and frees the memory they took up.
+	/// Live gas mixtures are never moved, since byond holds their indices directly.
+	/// Returns the amount of gas mixtures removed.
+	/// # Panics
+	/// If `GAS_MIXTURES` or `NEXT_GAS_IDS` haven't been initialized, somehow.
+	pub fn shrink_to_fit() -> usize {
+		let mut gas_lock = GAS_MIXTURES.write();
+		let mut ids_lock = NEXT_GAS_IDS.write();
+		let gas_mixtures = gas_lock.as_mut().unwrap();
+		let next_gas_ids = ids_lock.as_mut().unwrap();
+		let cur_len = gas_mixtures.len();
+		next_gas_ids.sort_unstable_by(|a, b| b.cmp(a));
+		let trailing = next_gas_ids
+			.iter()
+			.enumerate()
+			.take_while(|&(i, &id)| id + i + 1 == cur_len)
+			.count();
+		next_gas_ids.drain(..trailing);
+		gas_mixtures.truncate(cur_len - trailing);
+		gas_mixtures.shrink_to_fit();
+		trailing
+	}
+}
+
+/// Gets the index of the given value's gas mixture in the arena.
+/// # Errors
+/// If a gasmixture ID is not a number.
+pub fn mix_id(mix: &Value) -> Result<usize, Runtime> {
+	Ok(mix
+		.get_number(byond_string!("_extools_pointer_gasmixture"))
+		.map_err(|_| {
+			runtime!(
+				"Attempt to interpret non-number value as number {} {}:{}",
+				std::file!(),
+				std::line!(),
+				std::column!()
+			)
+		})?
+		.to_bits() as usize)
 }
 
 /// Gets the mix for the given value, and calls the provided closure with a reference to that mix as an argument.
@@ -6,6 +6,8 @@ pub const R_IDEAL_GAS_EQUATION: f32 = 8.31;
 pub const ONE_ATMOSPHERE: f32 = 101.325;
 ///  -270.3degC
 pub const TCMB: f32 = 2.7;
+/// Default ceiling for temperature clamping
+pub const MAX_TEMPERATURE: f32 = 1.0e9;
 ///  -48.15degC
 pub const TCRYO: f32 = 225.0;
 ///  0degC
@@ -16,6 +18,8 @@ pub const T20C: f32 = 293.15;
 pub const GAS_MIN_MOLES: f32 = 0.0001;
 /// Heat capacities below which heat will be considered 0.
 pub const MINIMUM_HEAT_CAPACITY: f32 = 0.0003;
+/// Stands in for the physical constants in the Sackur-Tetrode equation, for specific entropy.
+pub const IDEAL_GAS_ENTROPY_CONSTANT: f32 = 1164.0;
 
 /// liters in a cell
 pub const CELL_VOLUME: f32 = 2500.0;
@@ -57,6 +61,8 @@ pub const MINIMUM_TEMPERATURE_TO_MOVE: f32 = T20C + 100.0;
 pub const MINIMUM_TEMPERATURE_DELTA_TO_SUSPEND: f32 = 4.0;
 /// Minimum temperature difference before the gas temperatures are just set to be equal
 pub const MINIMUM_TEMPERATURE_DELTA_TO_CONSIDER: f32 = 0.5;
+/// Minimum pressure difference, in kilopascals, before any gas is moved to equalize two mixtures' pressures
+pub const MINIMUM_PRESSURE_DELTA_TO_EQUALIZE: f32 = 0.01;
 pub const MINIMUM_TEMPERATURE_FOR_SUPERCONDUCTION: f32 = T20C + 10.0;
 pub const MINIMUM_TEMPERATURE_START_SUPERCONDUCTION: f32 = T20C + 200.0;
 
@@ -88,6 +94,21 @@ const SPACE_TEMP: f64 = TCMB as f64;
 pub const RADIATION_FROM_SPACE: f64 =
 	STEFAN_BOLTZMANN_CONSTANT * SPACE_TEMP * SPACE_TEMP * SPACE_TEMP * SPACE_TEMP; // watts/meter^2
 
+/// CONTENT HASHING
+
+/// Moles are quantized to multiples of this before a mix's contents are hashed
+pub const CONTENT_HASH_MOLES_EPSILON: f32 = 0.001;
+/// Temperatures are quantized to multiples of this before a mix's contents are hashed
+pub const CONTENT_HASH_TEMPERATURE_EPSILON: f32 = 0.1;
+
+/// SERIALIZATION
+
+/// Version byte at the start of serialized gas mixtures; bump whenever the format changes
+pub const MIXTURE_SERIALIZATION_VERSION: u8 = 1;
+
+/// Version byte at the start of blobs made by `GasArena::serialize_all`; bump whenever the format changes
+pub const ARENA_SERIALIZATION_VERSION: u8 = 1;
+
 /// FIRE
 
 pub const FIRE_MINIMUM_TEMPERATURE_TO_SPREAD: f32 = 150.0 + T0C;
@@ -120,6 +141,8 @@ pub const MOLES_GAS_VISIBLE_STEP: f32 = 0.25;
 // TinyVec is used mostly to prevent too much heap stuff from going on, since there can be a LOT of reactions going.
 // ReactionIdentifier is 12 bytes, so this can be pretty generous.
 pub const MAX_REACTION_TINYVEC_SIZE: usize = 32;
+/// Default cap on reaction passes for react_stable
+pub const DEFAULT_MAX_REACTION_PASSES: usize = 5;
 
 bitflags! {
 	/// return values for reactions (bitflags)
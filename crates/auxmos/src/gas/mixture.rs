@@ -8,15 +8,26 @@ use atomic_float::AtomicF32;
 
 use tinyvec::TinyVec;
 
-use crate::reaction::{Reaction, ReactionPriority};
+use crate::reaction::{
+	current_reaction_tick, Reaction, ReactionEnv, ReactionIdentifier, ReactionOrder,
+};
 
 use super::{
-	constants::*, gas_visibility, total_num_gases, with_reactions, with_specific_heats, GasIDX,
+	constants::*, gas_idx_from_string, gas_visibility, total_num_gases, with_gas_info,
+	with_reactions, with_specific_heats, GasIDX,
+};
+
+use std::sync::{
+	atomic::{
+		AtomicBool, AtomicU32, AtomicU64, AtomicUsize,
+		Ordering::{Acquire, Relaxed, Release},
+	},
+	Arc,
 };
 
-use std::sync::atomic::{AtomicU64, Ordering::Relaxed};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
-use std::collections::BTreeMap;
+use fxhash::FxBuildHasher;
 
 type SpecificFireInfo = (usize, f32, f32);
 
@@ -52,6 +63,276 @@ impl GasCache {
 	pub fn set(&self, v: f32) {
 		self.0.store(v, Relaxed);
 	}
+	#[cfg(any(test, feature = "cache_audit"))]
+	pub fn peek(&self) -> Option<f32> {
+		Some(self.0.load(Relaxed)).filter(|x| !x.is_nan())
+	}
+}
+
+/// A set of gas indices, one bit each. One word covers the first 64 gases, and it grows for registries bigger than that.
+#[derive(Clone, Default, Debug)]
+pub struct BitSet(TinyVec<[u64; 1]>);
+
+impl BitSet {
+	/// Makes a set of the given gas indices.
+	pub fn from_indices(indices: impl IntoIterator<Item = GasIDX>) -> Self {
+		let mut set = Self::default();
+		for idx in indices {
+			let word = idx / 64;
+			if set.0.len() <= word {
+				set.0.resize(word + 1, 0);
+			}
+			set.0[word] |= 1 << (idx % 64);
+		}
+		set
+	}
+	/// Makes a set of the first 64 gas indices, one bit each.
+	pub fn from_word(word: u64) -> Self {
+		let mut set = Self::default();
+		set.0.push(word);
+		set
+	}
+	/// Whether every index in the other set is in this one too.
+	pub fn is_superset(&self, other: &Self) -> bool {
+		other
+			.0
+			.iter()
+			.enumerate()
+			.all(|(i, &word)| word & !self.0.get(i).copied().unwrap_or(0) == 0)
+	}
+}
+
+// The presence mask of mixes with no more than 64 gases, worked out the first time it's asked for after the gases change.
+#[derive(Default)]
+struct PresenceCache {
+	mask: AtomicU64,
+	valid: AtomicBool,
+}
+
+impl Clone for PresenceCache {
+	fn clone(&self) -> Self {
+		Self {
+			mask: AtomicU64::new(self.mask.load(Relaxed)),
+			valid: AtomicBool::new(self.valid.load(Acquire)),
+		}
+	}
+}
+
+impl PresenceCache {
+	pub fn invalidate(&mut self) {
+		*self.valid.get_mut() = false;
+	}
+	pub fn get_or_else(&self, f: impl FnOnce() -> u64) -> u64 {
+		if self.valid.load(Acquire) {
+			return self.mask.load(Relaxed);
+		}
+		let mask = f();
+		self.mask.store(mask, Relaxed);
+		self.valid.store(true, Release);
+		mask
+	}
+}
+
+// Whether heat capacity caches are checked against a full recalculation every time they're used.
+#[cfg(any(test, feature = "cache_audit"))]
+static CACHE_AUDIT: AtomicBool = AtomicBool::new(false);
+
+/// Turns checking heat capacity caches every time they're used on or off. Off by default.
+#[cfg(any(test, feature = "cache_audit"))]
+pub fn set_cache_audit(enabled: bool) {
+	CACHE_AUDIT.store(enabled, Relaxed);
+}
+
+/// The simulation's numerical floors: gases with `min_moles` or less are thrown away,
+/// and temperatures don't go below `min_temperature`.
+#[derive(Copy, Clone)]
+pub struct SimFloors {
+	pub min_moles: f32,
+	pub min_temperature: f32,
+}
+
+// Read constantly by every thread doing gas work, so these are the floors' bits in relaxed atomics, which load like plain statics.
+static MIN_MOLES_BITS: AtomicU32 = AtomicU32::new(GAS_MIN_MOLES.to_bits());
+static MIN_TEMPERATURE_BITS: AtomicU32 = AtomicU32::new(TCMB.to_bits());
+
+/// The current simulation floors. Starts as `GAS_MIN_MOLES` and `TCMB`.
+/// Hot loops should read this once, before starting, rather than for every mix.
+#[inline]
+#[must_use]
+pub fn sim_floors() -> SimFloors {
+	SimFloors {
+		min_moles: f32::from_bits(MIN_MOLES_BITS.load(Relaxed)),
+		min_temperature: f32::from_bits(MIN_TEMPERATURE_BITS.load(Relaxed)),
+	}
+}
+
+/// Sets the simulation floors. Changing them mid-round can make gas appear or vanish, or temperatures jump,
+/// for a moment while mixes that were fine under the old floors get cleaned up under the new ones.
+/// Work already running can see the old floors until it next reads them.
+pub fn set_sim_floors(floors: SimFloors) {
+	MIN_MOLES_BITS.store(floors.min_moles.to_bits(), Relaxed);
+	MIN_TEMPERATURE_BITS.store(floors.min_temperature.to_bits(), Relaxed);
+}
+
+/// Why `Mixture::merge_checked` didn't merge.
+pub enum MergeRefusal {
+	/// We're immutable, so the merge couldn't have changed us.
+	Immutable,
+	/// The pressure the merge would have left us at, which is above the maximum.
+	Overpressure(f32),
+}
+
+// Extra gas slots to reserve, past however many gases are registered, whenever a mix's gas vector has to grow.
+static GAS_VECTOR_HEADROOM: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets how many extra gas slots, past the number of registered gases, a mix reserves whenever its gases have to be reallocated.
+/// Capacity is rounded up to the next power of two after that anyway, so gases registered mid-round
+/// mostly fit into space mixes already have instead of making every mix reallocate.
+pub fn set_gas_vector_headroom(slots: usize) {
+	GAS_VECTOR_HEADROOM.store(slots, Relaxed);
+}
+
+// The capacity to give a gas vector that needs to hold at least `size` gases.
+fn gas_vector_capacity(size: usize) -> usize {
+	(total_num_gases() + GAS_VECTOR_HEADROOM.load(Relaxed))
+		.max(size)
+		.next_power_of_two()
+}
+
+// Tells byond that a heat capacity cache was wrong. Called off the main thread too, so it goes through a callback.
+#[cfg(any(test, feature = "cache_audit"))]
+fn report_cache_desync(cached: f32, actual: f32) {
+	let message = format!(
+		"Gas mixture heat capacity cache desync: cached {} but actually {}",
+		cached, actual
+	);
+	drop(
+		auxcallback::byond_callback_sender()
+			.try_send(Box::new(move || Err(auxtools::runtime!("{}", message)))),
+	);
+}
+
+/// Splits the first `amt` bytes off of the reader, for deserialization.
+pub(crate) fn take_bytes<'a>(
+	reader: &mut &'a [u8],
+	amt: usize,
+) -> Result<&'a [u8], auxtools::Runtime> {
+	if reader.len() < amt {
+		return Err(auxtools::runtime!("Serialized gas mixture is truncated!"));
+	}
+	let (taken, rest) = reader.split_at(amt);
+	*reader = rest;
+	Ok(taken)
+}
+
+fn take_u16(reader: &mut &[u8]) -> Result<u16, auxtools::Runtime> {
+	Ok(u16::from_le_bytes(
+		take_bytes(reader, 2)?.try_into().unwrap(),
+	))
+}
+
+fn take_f32(reader: &mut &[u8]) -> Result<f32, auxtools::Runtime> {
+	Ok(f32::from_le_bytes(
+		take_bytes(reader, 4)?.try_into().unwrap(),
+	))
+}
+
+pub(crate) fn take_u32(reader: &mut &[u8]) -> Result<u32, auxtools::Runtime> {
+	Ok(u32::from_le_bytes(
+		take_bytes(reader, 4)?.try_into().unwrap(),
+	))
+}
+
+fn take_volume(reader: &mut &[u8]) -> Result<f32, auxtools::Runtime> {
+	let volume = take_f32(reader)?;
+	if !volume.is_normal() || volume < 0.0 {
+		return Err(auxtools::runtime!(
+			"Invalid serialized gas mixture volume: {}",
+			volume
+		));
+	}
+	Ok(volume)
+}
+
+/// Writes a table of (gas index, string ID length, string ID) for the given gases, so readers can remap the indices.
+pub(crate) fn write_gas_table(indices: impl ExactSizeIterator<Item = GasIDX>, bytes: &mut Vec<u8>) {
+	bytes.extend_from_slice(&(indices.len() as u16).to_le_bytes());
+	with_gas_info(|gas_info| {
+		for idx in indices {
+			let id = gas_info[idx].id.as_bytes();
+			bytes.extend_from_slice(&(idx as u16).to_le_bytes());
+			bytes.extend_from_slice(&(id.len() as u16).to_le_bytes());
+			bytes.extend_from_slice(id);
+		}
+	});
+}
+
+/// Reads a table made by `write_gas_table`, pairing each written index with the current index of that gas, if it still exists.
+pub(crate) fn read_gas_table(
+	reader: &mut &[u8],
+) -> Result<Vec<(u16, Option<GasIDX>)>, auxtools::Runtime> {
+	let table_len = take_u16(reader)?;
+	let mut remap = Vec::with_capacity(table_len as usize);
+	for _ in 0..table_len {
+		let old_idx = take_u16(reader)?;
+		let id_len = take_u16(reader)?;
+		let id = std::str::from_utf8(take_bytes(reader, id_len as usize)?)
+			.map_err(|_| auxtools::runtime!("Serialized gas mixture has a non-UTF-8 gas ID!"))?;
+		remap.push((old_idx, gas_idx_from_string(id).ok()));
+	}
+	Ok(remap)
+}
+
+fn write_gases(gases: &[(GasIDX, f32)], bytes: &mut Vec<u8>) {
+	bytes.extend_from_slice(&(gases.len() as u16).to_le_bytes());
+	for &(idx, amt) in gases {
+		bytes.extend_from_slice(&(idx as u16).to_le_bytes());
+		bytes.extend_from_slice(&amt.to_le_bytes());
+	}
+}
+
+fn read_gases(
+	reader: &mut &[u8],
+	remap: &[(u16, Option<GasIDX>)],
+) -> Result<Vec<(GasIDX, f32)>, auxtools::Runtime> {
+	let gases_len = take_u16(reader)?;
+	let mut entries = Vec::with_capacity(gases_len as usize);
+	for _ in 0..gases_len {
+		let old_idx = take_u16(reader)?;
+		let amt = take_f32(reader)?;
+		let new_idx = remap
+			.iter()
+			.find(|&&(idx, _)| idx == old_idx)
+			.ok_or_else(|| {
+				auxtools::runtime!(
+					"Serialized gas mixture refers to gas index {} missing from its table!",
+					old_idx
+				)
+			})?
+			.1;
+		if let Some(idx) = new_idx {
+			entries.push((idx, amt));
+		}
+	}
+	Ok(entries)
+}
+
+/// As `with_specific_heats`, but with the given per-mix specific heat overrides applied.
+fn with_overridden_specific_heats<T>(
+	overrides: &[(GasIDX, f32)],
+	f: impl FnOnce(&[f32]) -> T,
+) -> T {
+	if overrides.is_empty() {
+		with_specific_heats(f)
+	} else {
+		let mut heats = with_specific_heats(<[f32]>::to_vec);
+		for &(idx, heat) in overrides {
+			if let Some(h) = heats.get_mut(idx) {
+				*h = heat;
+			}
+		}
+		f(&heats)
+	}
 }
 
 pub fn visibility_step(gas_amt: f32) -> u32 {
@@ -64,7 +345,7 @@ pub fn visibility_step(gas_amt: f32) -> u32 {
 /// The data structure representing a Space Station 13 gas mixture.
 /// Unlike Monstermos, this doesn't have the archive built-in; instead,
 /// the archive is a feature of the turf grid, only existing during
-/// turf processing.
+/// turf processing. The archive here is only for rolling back reactions.
 /// Also missing is `last_share`; due to the usage of Rust,
 /// processing no longer requires sleeping turfs. Instead, we're using
 /// a proper, fully-simulated FDM system, much like LINDA but without
@@ -76,6 +357,15 @@ pub struct Mixture {
 	min_heat_capacity: f32,
 	moles: TinyVec<[f32; 8]>,
 	cached_heat_capacity: GasCache,
+	cached_total_moles: GasCache,
+	cached_presence: PresenceCache,
+	heat_capacity_overrides: Vec<(GasIDX, f32)>,
+	archived: Option<MixtureSnapshot>,
+	history: Option<MixtureHistory>,
+	last_reacted: HashMap<ReactionIdentifier, u32, FxBuildHasher>,
+	reactions_suppressed: bool,
+	// The reaction tick fires last burned fuel in this mix, how many moles they burned during it, and during the tick before it.
+	fuel_burnt: Option<(u32, f32, f32)>,
 	immutable: bool,
 }
 
@@ -96,6 +386,14 @@ impl Mixture {
 			min_heat_capacity: 0.0,
 			immutable: false,
 			cached_heat_capacity: GasCache::default(),
+			cached_total_moles: GasCache::default(),
+			cached_presence: PresenceCache::default(),
+			heat_capacity_overrides: Vec::new(),
+			archived: None,
+			history: None,
+			last_reacted: HashMap::default(),
+			reactions_suppressed: false,
+			fuel_burnt: None,
 		}
 	}
 	/// Makes an empty gas mixture with the given volume.
@@ -105,6 +403,54 @@ impl Mixture {
 		ret.volume = vol;
 		ret
 	}
+	/// Makes a gas mixture with the given volume and temperature, holding enough gas to be at the given pressure.
+	/// The gas is split between the gases in the composition by their ratios, which are normalized, so they don't need to add up to 1.
+	/// Gases with ratios that aren't positive are left out.
+	/// # Errors
+	/// If the volume or temperature isn't positive, or if the pressure isn't zero or more.
+	pub fn from_pressure(
+		volume: f32,
+		pressure: f32,
+		temperature: f32,
+		composition: &[(GasIDX, f32)],
+	) -> Result<Self, auxtools::Runtime> {
+		if volume.is_nan() || volume <= 0.0 {
+			return Err(auxtools::runtime!(
+				"Attempted to make a gas mixture with a non-positive volume: {}",
+				volume
+			));
+		}
+		if temperature.is_nan() || temperature <= 0.0 {
+			return Err(auxtools::runtime!(
+				"Attempted to make a gas mixture with a non-positive temperature: {}",
+				temperature
+			));
+		}
+		if !pressure.is_finite() || pressure < 0.0 {
+			return Err(auxtools::runtime!(
+				"Attempted to make a gas mixture with an invalid pressure: {}",
+				pressure
+			));
+		}
+		let mut mix = Self::from_vol(volume);
+		mix.temperature = temperature;
+		let total_ratio: f32 = composition
+			.iter()
+			.map(|&(_, ratio)| ratio)
+			.filter(|&ratio| ratio > 0.0)
+			.sum();
+		if total_ratio > 0.0 {
+			let total_moles = pressure * volume / (R_IDEAL_GAS_EQUATION * temperature);
+			for &(idx, ratio) in composition.iter().filter(|&&(_, ratio)| ratio > 0.0) {
+				mix.adjust_moles(idx, total_moles * ratio / total_ratio);
+			}
+		}
+		Ok(mix)
+	}
+	/// Changes the volume of the mix without changing its moles or temperature, so its pressure rescales accordingly.
+	pub fn set_volume_preserve_moles(&mut self, new_volume: f32) {
+		self.volume = new_volume;
+	}
 	/// Returns if any data is corrupt.
 	pub fn is_corrupt(&self) -> bool {
 		!self.temperature.is_normal() || self.moles.len() > total_num_gases()
@@ -120,20 +466,69 @@ impl Mixture {
 	pub fn get_temperature(&self) -> f32 {
 		self.temperature
 	}
-	/// Sets the temperature, if the mix isn't immutable. T
+	/// Sets the temperature, if the mix isn't immutable. Moles stay as they are, so thermal energy follows. T
+	/// Anything below the minimum temperature, `TCMB` unless changed by `set_sim_floors`, is set to it,
+	/// and NaN or infinite temperatures are ignored.
+	/// An empty mix keeps the temperature, but has no thermal energy until gas is added.
 	pub fn set_temperature(&mut self, temp: f32) {
-		if !self.immutable && temp.is_normal() {
-			self.temperature = temp;
+		self.set_temperature_with_floors(temp, sim_floors());
+	}
+	/// Like `set_temperature`, against floors the caller has already read.
+	pub fn set_temperature_with_floors(&mut self, temp: f32, floors: SimFloors) {
+		if !self.immutable && temp.is_finite() {
+			self.temperature = temp.max(floors.min_temperature);
 		}
 	}
+	/// Clamps the temperature between `min` and `max`, if the mix isn't immutable.
+	pub fn clamp_temperature(&mut self, min: f32, max: f32) {
+		self.set_temperature(self.temperature.max(min).min(max));
+	}
 	/// Sets the minimum heat capacity of this mix.
 	pub fn set_min_heat_capacity(&mut self, amt: f32) {
 		self.min_heat_capacity = amt;
 	}
+	/// Makes this mix use the given specific heat for the given gas instead of the global one.
+	pub fn set_heat_capacity_override(&mut self, idx: GasIDX, specific_heat: f32) {
+		match self
+			.heat_capacity_overrides
+			.iter_mut()
+			.find(|(i, _)| *i == idx)
+		{
+			Some((_, heat)) => *heat = specific_heat,
+			None => self.heat_capacity_overrides.push((idx, specific_heat)),
+		}
+		self.cached_heat_capacity.invalidate();
+	}
+	/// Makes this mix use the global specific heat for the given gas again.
+	pub fn clear_heat_capacity_override(&mut self, idx: GasIDX) {
+		self.heat_capacity_overrides.retain(|&(i, _)| i != idx);
+		self.cached_heat_capacity.invalidate();
+	}
+	/// Sets the heat capacity cache after merging in another mix, unless either has overrides making the sum wrong.
+	fn cache_merged_heat_capacity(&self, giver: &Self, combined_heat_capacity: f32) {
+		// minimum heat capacities don't add up, so those have to be recalculated too
+		if self.heat_capacity_overrides.is_empty()
+			&& giver.heat_capacity_overrides.is_empty()
+			&& self.min_heat_capacity == 0.0
+			&& giver.min_heat_capacity == 0.0
+		{
+			self.cached_heat_capacity.set(combined_heat_capacity);
+		} else {
+			self.cached_heat_capacity.invalidate();
+		}
+	}
 	/// Returns an iterator over the gas keys and mole amounts thereof.
 	pub fn enumerate(&self) -> impl Iterator<Item = (GasIDX, f32)> + '_ {
 		self.moles.iter().copied().enumerate()
 	}
+	/// Returns the indices of the gases with more than the minimum moles in the mix.
+	pub fn present_gases(&self) -> Vec<GasIDX> {
+		let min_moles = sim_floors().min_moles;
+		let is_present = |&(_, amt): &(GasIDX, f32)| amt > min_moles;
+		let mut present = Vec::with_capacity(self.enumerate().filter(is_present).count());
+		present.extend(self.enumerate().filter(is_present).map(|(idx, _)| idx));
+		present
+	}
 	/// Allows closures to iterate over each gas.
 	/// # Errors
 	/// If the closure errors.
@@ -153,6 +548,9 @@ impl Mixture {
 		&mut self,
 		mut f: impl FnMut(GasIDX, &mut f32) -> Result<(), auxtools::Runtime>,
 	) -> Result<(), auxtools::Runtime> {
+		self.cached_heat_capacity.invalidate();
+		self.cached_total_moles.invalidate();
+		self.cached_presence.invalidate();
 		for (i, g) in self.moles.iter_mut().enumerate() {
 			f(i, g)?;
 		}
@@ -162,6 +560,15 @@ impl Mixture {
 	pub fn get_moles(&self, idx: GasIDX) -> f32 {
 		self.moles.get(idx).copied().unwrap_or(0.0)
 	}
+	/// As `get_moles`, but also returns 0 for any index that isn't in the gas registry,
+	/// even if the mix still has moles there from before the registry changed.
+	pub fn get_moles_safe(&self, idx: GasIDX) -> f32 {
+		if idx < total_num_gases() {
+			self.get_moles(idx)
+		} else {
+			0.0
+		}
+	}
 	/// Sets the mix to be internally immutable. Rust doesn't know about any of this, obviously.
 	pub fn mark_immutable(&mut self) {
 		self.immutable = true;
@@ -170,33 +577,91 @@ impl Mixture {
 	pub fn is_immutable(&self) -> bool {
 		self.immutable
 	}
+	/// Sets whether reactions are kept from happening in this mix, whatever's in it.
+	pub fn set_reactions_suppressed(&mut self, suppressed: bool) {
+		self.reactions_suppressed = suppressed;
+	}
+	/// Returns whether reactions are kept from happening in this mix.
+	pub fn reactions_suppressed(&self) -> bool {
+		self.reactions_suppressed
+	}
 	fn maybe_expand(&mut self, size: usize) {
 		if self.moles.len() < size {
+			if self.moles.capacity() < size {
+				self.moles
+					.reserve(gas_vector_capacity(size) - self.moles.len());
+			}
 			self.moles.resize(size, 0.0);
 		}
 	}
 	/// If mix is not immutable, sets the gas at the given `idx` to the given `amt`.
 	pub fn set_moles(&mut self, idx: GasIDX, amt: f32) {
+		self.set_moles_with_floors(idx, amt, sim_floors());
+	}
+	/// Like `set_moles`, against floors the caller has already read.
+	pub fn set_moles_with_floors(&mut self, idx: GasIDX, amt: f32, floors: SimFloors) {
 		if !self.immutable
 			&& idx < total_num_gases()
-			&& (idx <= self.moles.len() || (amt > GAS_MIN_MOLES && amt.is_normal()))
+			&& (idx <= self.moles.len() || (amt > floors.min_moles && amt.is_normal()))
 		{
 			self.maybe_expand((idx + 1) as usize);
 			unsafe {
 				*self.moles.get_unchecked_mut(idx) = amt;
 			};
 			self.cached_heat_capacity.invalidate();
+			self.cached_total_moles.invalidate();
+			self.cached_presence.invalidate();
+		}
+	}
+	/// As `set_moles`, but sets many gases at once, only invalidating the heat capacity cache once at the end.
+	/// Entries with a NaN, infinite or negative amount are skipped.
+	pub fn set_moles_bulk(&mut self, entries: &[(GasIDX, f32)]) {
+		self.set_moles_bulk_with_floors(entries, sim_floors());
+	}
+	/// Like `set_moles_bulk`, against floors the caller has already read.
+	pub fn set_moles_bulk_with_floors(&mut self, entries: &[(GasIDX, f32)], floors: SimFloors) {
+		if self.immutable {
+			return;
+		}
+		let num_gases = total_num_gases();
+		let min_moles = floors.min_moles;
+		self.maybe_expand(
+			entries
+				.iter()
+				.filter_map(|&(i, amt)| {
+					(i < num_gases && amt > min_moles && amt.is_normal()).then_some(i + 1)
+				})
+				.max()
+				.unwrap_or(0),
+		);
+		for &(idx, amt) in entries {
+			if !amt.is_finite() || amt < 0.0 {
+				continue;
+			}
+			if let Some(r) = self.moles.get_mut(idx) {
+				*r = amt;
+			}
 		}
+		self.cached_heat_capacity.invalidate();
+		self.cached_total_moles.invalidate();
+		self.cached_presence.invalidate();
+		self.garbage_collect_with_floors(floors);
 	}
 	pub fn adjust_moles(&mut self, idx: GasIDX, amt: f32) {
+		self.adjust_moles_with_floors(idx, amt, sim_floors());
+	}
+	/// Like `adjust_moles`, against floors the caller has already read.
+	pub fn adjust_moles_with_floors(&mut self, idx: GasIDX, amt: f32, floors: SimFloors) {
 		if !self.immutable && amt.is_normal() && idx < total_num_gases() {
 			self.maybe_expand((idx + 1) as usize);
 			let r = unsafe { self.moles.get_unchecked_mut(idx) };
 			*r += amt;
 			if amt <= 0.0 {
-				self.garbage_collect();
+				self.garbage_collect_with_floors(floors);
 			}
 			self.cached_heat_capacity.invalidate();
+			self.cached_total_moles.invalidate();
+			self.cached_presence.invalidate();
 		}
 	}
 	pub fn adjust_multi(&mut self, adjustments: &[(usize, f32)]) {
@@ -223,15 +688,44 @@ impl Mixture {
 			}
 			if dirty {
 				self.cached_heat_capacity.invalidate();
+				self.cached_total_moles.invalidate();
+				self.cached_presence.invalidate();
 			}
 			if should_collect {
 				self.garbage_collect();
 			}
 		}
 	}
+	/// Applies a reaction's mole changes, negative for gases used up and positive for gases made.
+	/// If any gas used would go below zero, the whole reaction is scaled down so that gas, the limiting reagent, just runs out.
+	/// Returns how much of the full reaction happened, from 0 to 1.
+	/// Takes the sim floors from the caller, who should read them once for the whole reaction batch.
+	pub fn react_limited(&mut self, changes: &[(GasIDX, f32)], floors: SimFloors) -> f32 {
+		if self.immutable {
+			return 0.0;
+		}
+		let mut net: Vec<(GasIDX, f32)> = Vec::with_capacity(changes.len());
+		for &(idx, amt) in changes {
+			match net.iter_mut().find(|(i, _)| *i == idx) {
+				Some((_, total)) => *total += amt,
+				None => net.push((idx, amt)),
+			}
+		}
+		let extent = net
+			.iter()
+			.filter(|&&(_, amt)| amt < 0.0)
+			.map(|&(idx, amt)| self.get_moles(idx) / -amt)
+			.fold(1.0_f32, f32::min)
+			.max(0.0);
+		for (idx, amt) in &mut net {
+			*amt = amt.mul_add(extent, self.get_moles(*idx)).max(0.0);
+		}
+		self.set_moles_bulk_with_floors(&net, floors);
+		extent
+	}
 	#[inline(never)] // mostly this makes it so that heat_capacity itself is inlined
 	fn slow_heat_capacity(&self) -> f32 {
-		with_specific_heats(|heats| {
+		with_overridden_specific_heats(&self.heat_capacity_overrides, |heats| {
 			self.moles
 				.iter()
 				.copied()
@@ -240,8 +734,34 @@ impl Mixture {
 		})
 		.max(self.min_heat_capacity)
 	}
+	/// Checks the cached heat capacity, if there is one, against one calculated from scratch.
+	/// Returns false if they don't match, meaning something changed the mix without invalidating the cache.
+	#[cfg(any(test, feature = "cache_audit"))]
+	pub fn verify_cache(&self) -> bool {
+		self.cached_heat_capacity.peek().map_or(true, |cached| {
+			let actual = self.slow_heat_capacity();
+			(cached - actual).abs() <= MINIMUM_HEAT_CAPACITY.max(actual.abs() * 1.0e-4)
+		})
+	}
 	/// The heat capacity of the material. [joules?]/mole-kelvin.
 	pub fn heat_capacity(&self) -> f32 {
+		#[cfg(any(test, feature = "cache_audit"))]
+		if CACHE_AUDIT.load(Relaxed) {
+			let in_sync = self.verify_cache();
+			debug_assert!(
+				in_sync,
+				"Gas mixture heat capacity cache desync: cached {:?} but actually {}",
+				self.cached_heat_capacity.peek(),
+				self.slow_heat_capacity()
+			);
+			if !in_sync {
+				report_cache_desync(
+					self.cached_heat_capacity.peek().unwrap_or_default(),
+					self.slow_heat_capacity(),
+				);
+				self.cached_heat_capacity.invalidate();
+			}
+		}
 		self.cached_heat_capacity
 			.get_or_else(|| self.slow_heat_capacity())
 	}
@@ -250,22 +770,204 @@ impl Mixture {
 		self.moles
 			.get(idx)
 			.filter(|amt| amt.is_normal())
-			.map_or(0.0, |amt| amt * with_specific_heats(|heats| heats[idx]))
+			.map_or(0.0, |amt| {
+				amt * with_overridden_specific_heats(&self.heat_capacity_overrides, |heats| {
+					heats[idx]
+				})
+			})
+	}
+	/// Each present gas's share of the heat capacity, its moles times its specific heat, the terms `heat_capacity` sums up.
+	/// These don't include `min_heat_capacity`, so if that's what the mix's heat capacity is, they add up to less.
+	pub fn heat_capacity_breakdown(&self) -> Vec<(GasIDX, f32)> {
+		let min_moles = sim_floors().min_moles;
+		with_overridden_specific_heats(&self.heat_capacity_overrides, |heats| {
+			self.enumerate()
+				.filter(|&(_, amt)| amt > min_moles)
+				.map(|(idx, amt)| (idx, amt * heats[idx]))
+				.collect()
+		})
+	}
+	/// Specific entropy, per mole. Each gas's is a Sackur-Tetrode-style `R * (ln(K * V / n * (c * T)^1.5) + 2.5)`,
+	/// with `K` being `IDEAL_GAS_ENTROPY_CONSTANT` and `c` the gas's specific heat standing in for its mass,
+	/// and the mix's is those weighted by mole fraction. Gases without a specific heat are left out. Zero if the mix is empty.
+	pub fn specific_entropy(&self) -> f32 {
+		let min_moles = sim_floors().min_moles;
+		let total_moles = self.total_moles();
+		if total_moles < min_moles || self.volume <= 0.0 {
+			return 0.0;
+		}
+		with_overridden_specific_heats(&self.heat_capacity_overrides, |heats| {
+			self.enumerate()
+				.filter(|&(idx, amt)| amt > min_moles && heats[idx] > 0.0)
+				.map(|(idx, amt)| {
+					let thermal = (heats[idx] * self.temperature).powf(1.5);
+					let gas_entropy = R_IDEAL_GAS_EQUATION
+						* ((IDEAL_GAS_ENTROPY_CONSTANT * self.volume / amt * thermal).ln() + 2.5);
+					gas_entropy * amt / total_moles
+				})
+				.sum()
+		})
+	}
+	/// The mass of the given gas in the mix, in grams. Zero if the gas's molar mass isn't known.
+	pub fn gas_mass(&self, idx: GasIDX) -> f32 {
+		with_gas_info(|gas_info| {
+			gas_info
+				.get(idx)
+				.and_then(|gas| gas.molar_mass)
+				.map_or(0.0, |molar_mass| self.get_moles(idx) * molar_mass)
+		})
+	}
+	/// The total mass of the mix, in grams. Gases whose molar mass isn't known don't count.
+	pub fn mass(&self) -> f32 {
+		with_gas_info(|gas_info| {
+			self.enumerate()
+				.filter_map(|(idx, amt)| Some(amt * gas_info.get(idx)?.molar_mass?))
+				.sum()
+		})
+	}
+	/// The gases in the mix that are below their condensation point, and how many moles of each would condense.
+	/// Only reads the mix; nothing is actually removed. Gases without a condensation point are never returned.
+	pub fn condensable_gases(&self) -> Vec<(GasIDX, f32)> {
+		let min_moles = sim_floors().min_moles;
+		with_gas_info(|gas_info| {
+			self.enumerate()
+				.filter(|&(idx, amt)| {
+					amt > min_moles
+						&& gas_info
+							.get(idx)
+							.and_then(|gas| gas.condensation_point)
+							.map_or(false, |point| self.temperature < point)
+				})
+				.collect()
+		})
+	}
+	/// How many moles of the given gas are over what the mix can hold at our temperature, going by the gas's saturation curve.
+	/// Only reads the mix; nothing is actually removed. Zero for gases without a saturation curve.
+	pub fn saturation_excess(&self, gas: GasIDX) -> f32 {
+		let curve =
+			with_gas_info(|gas_info| gas_info.get(gas).and_then(|info| info.saturation_curve));
+		curve.map_or(0.0, |curve| {
+			let saturated_moles = curve.pressure_at(self.temperature) * self.volume
+				/ (R_IDEAL_GAS_EQUATION * self.temperature);
+			(self.get_moles(gas) - saturated_moles).max(0.0)
+		})
 	}
 	/// The total mole count of the mixture. Moles.
 	pub fn total_moles(&self) -> f32 {
 		self.moles.iter().sum()
 	}
+	/// Whether the mix has less than the minimum moles in total. Cached, so cheaper than summing the moles every time.
+	pub fn is_empty(&self) -> bool {
+		self.is_empty_with_floors(sim_floors())
+	}
+	/// Like `is_empty`, against floors the caller has already read.
+	pub fn is_empty_with_floors(&self, floors: SimFloors) -> bool {
+		self.cached_total_moles.get_or_else(|| self.total_moles()) < floors.min_moles
+	}
 	/// Pressure. Kilopascals.
 	pub fn return_pressure(&self) -> f32 {
 		self.total_moles() * R_IDEAL_GAS_EQUATION * self.temperature / self.volume
 	}
+	/// The pressure the mix would be at if it had the given volume instead, in kilopascals.
+	pub fn pressure_at_volume(&self, volume: f32) -> f32 {
+		self.total_moles() * R_IDEAL_GAS_EQUATION * self.temperature / volume
+	}
+	/// The partial pressure of each gas in the mix above the minimum moles. Kilopascals.
+	pub fn partial_pressures(&self) -> Vec<(GasIDX, f32)> {
+		let min_moles = sim_floors().min_moles;
+		let pressure_per_mole = R_IDEAL_GAS_EQUATION * self.temperature / self.volume;
+		self.enumerate()
+			.filter(|&(_, amt)| amt > min_moles)
+			.map(|(i, amt)| (i, amt * pressure_per_mole))
+			.collect()
+	}
+	/// The given gas's share of the mix's pressure, from 0 to 1. Zero if the mix is empty.
+	pub fn get_breathable_fraction(&self, idx: GasIDX) -> f32 {
+		let total_moles = self.total_moles();
+		if total_moles < sim_floors().min_moles {
+			0.0
+		} else {
+			self.get_moles(idx) / total_moles
+		}
+	}
+	/// The gas we have the most moles of and its mole fraction, or `None` if we're empty. Ties go to the lowest index.
+	pub fn dominant_gas(&self) -> Option<(GasIDX, f32)> {
+		let min_moles = sim_floors().min_moles;
+		let (idx, amt) = self.enumerate().filter(|&(_, amt)| amt > min_moles).fold(
+			None,
+			|best: Option<(GasIDX, f32)>, (idx, amt)| match best {
+				Some((_, best_amt)) if best_amt >= amt => best,
+				_ => Some((idx, amt)),
+			},
+		)?;
+		Some((idx, amt / self.total_moles()))
+	}
+	/// Whether the mix has at least the given amount of each given gas. Requirements below the minimum moles are treated as the minimum.
+	pub fn contains_at_least(&self, requirements: &[(GasIDX, f32)]) -> bool {
+		let min_moles = sim_floors().min_moles;
+		requirements
+			.iter()
+			.all(|&(idx, amt)| self.get_moles(idx) >= amt.max(min_moles))
+	}
+	/// Which gases this mix has at least the minimum moles of, for ruling out reactions that need gases it doesn't have
+	/// without going through their requirements. Cached until the gases change, unless the mix has more than 64 gases.
+	pub fn presence_mask(&self) -> BitSet {
+		let min_moles = sim_floors().min_moles;
+		let present = self
+			.moles
+			.iter()
+			.enumerate()
+			.filter_map(|(i, &amt)| (amt >= min_moles).then_some(i));
+		if self.moles.len() > 64 {
+			return BitSet::from_indices(present);
+		}
+		BitSet::from_word(
+			self.cached_presence
+				.get_or_else(|| present.fold(0, |mask, i| mask | (1 << i))),
+		)
+	}
 	/// Thermal energy. Joules?
 	pub fn thermal_energy(&self) -> f32 {
 		self.heat_capacity() * self.temperature
 	}
+	/// Thermal energy of exactly one gas in this mix: its moles times its specific heat times our temperature.
+	pub fn thermal_energy_of(&self, idx: GasIDX) -> f32 {
+		self.partial_heat_capacity(idx) * self.temperature
+	}
+	/// Merges the giver into us like `merge`, unless that would put us above `max_pressure`, for sealed containers that rupture.
+	/// The merged pressure is worked out before anything changes, so if it's too high neither mix is touched.
+	/// # Errors
+	/// If we're immutable, or with the pressure the merge would have left us at, if that's above `max_pressure`.
+	pub fn merge_checked(&mut self, giver: &Self, max_pressure: f32) -> Result<(), MergeRefusal> {
+		if self.immutable {
+			return Err(MergeRefusal::Immutable);
+		}
+		let our_heat_capacity = self.heat_capacity();
+		let other_heat_capacity = giver.heat_capacity();
+		let combined_heat_capacity = our_heat_capacity + other_heat_capacity;
+		// same as what merge would set our temperature to, floor included
+		let temperature = if combined_heat_capacity > MINIMUM_HEAT_CAPACITY {
+			((our_heat_capacity * self.temperature + other_heat_capacity * giver.temperature)
+				/ combined_heat_capacity)
+				.max(sim_floors().min_temperature)
+		} else {
+			self.temperature
+		};
+		let pressure =
+			(self.total_moles() + giver.total_moles()) * R_IDEAL_GAS_EQUATION * temperature
+				/ self.volume;
+		if pressure > max_pressure {
+			return Err(MergeRefusal::Overpressure(pressure));
+		}
+		self.merge(giver);
+		Ok(())
+	}
 	/// Merges one gas mixture into another.
 	pub fn merge(&mut self, giver: &Self) {
+		self.merge_with_floors(giver, sim_floors());
+	}
+	/// Like `merge`, against floors the caller has already read.
+	pub fn merge_with_floors(&mut self, giver: &Self, floors: SimFloors) {
 		if self.immutable {
 			return;
 		}
@@ -276,21 +978,91 @@ impl Mixture {
 			*a += b;
 		}
 		let combined_heat_capacity = our_heat_capacity + other_heat_capacity;
+		if combined_heat_capacity > MINIMUM_HEAT_CAPACITY {
+			self.set_temperature_with_floors(
+				(our_heat_capacity * self.temperature + other_heat_capacity * giver.temperature)
+					/ (combined_heat_capacity),
+				floors,
+			);
+		}
+		self.cache_merged_heat_capacity(giver, combined_heat_capacity);
+		self.cached_total_moles.invalidate();
+		self.cached_presence.invalidate();
+	}
+	/// Merges the giver into us, but caps each given gas at the given amount of moles.
+	/// Returns whatever was over the caps as a new mix, at the same temperature as us after merging.
+	pub fn merge_capped(&mut self, giver: &Self, caps: &[(GasIDX, f32)]) -> Self {
+		let mut overflow = Self::from_vol(giver.volume);
+		if self.immutable {
+			return overflow;
+		}
+		self.merge(giver);
+		let excess = caps
+			.iter()
+			.filter_map(|&(idx, cap)| {
+				let amt = self.get_moles(idx) - cap.max(0.0);
+				(amt > 0.0).then_some((idx, amt))
+			})
+			.collect::<Vec<_>>();
+		if !excess.is_empty() {
+			let removals = excess
+				.iter()
+				.map(|&(idx, amt)| (idx, -amt))
+				.collect::<Vec<_>>();
+			self.adjust_multi(&removals);
+			overflow.adjust_multi(&excess);
+			overflow.set_temperature(self.temperature);
+		}
+		overflow
+	}
+	/// Splits us evenly between the outputs, merging an equal share of our gases into each, then empties us.
+	/// # Errors
+	/// If there are no outputs.
+	pub fn split_evenly(&mut self, outputs: &mut [&mut Self]) -> Result<(), auxtools::Runtime> {
+		if outputs.is_empty() {
+			return Err(auxtools::runtime!(
+				"Attempted to split a gas mixture into zero outputs!"
+			));
+		}
+		let share = &*self * (1.0 / outputs.len() as f32);
+		for output in outputs.iter_mut() {
+			output.merge(&share);
+		}
+		self.clear();
+		Ok(())
+	}
+	/// Merges the given ratio of the giver's moles into us, without modifying the giver.
+	/// Temperature is weighted by the heat capacity actually transferred.
+	pub fn merge_fraction(&mut self, giver: &Self, r: f32) {
+		if self.immutable {
+			return;
+		}
+		let ratio = r.clamp(0.0, 1.0);
+		let our_heat_capacity = self.heat_capacity();
+		let other_heat_capacity = giver.heat_capacity() * ratio;
+		self.maybe_expand(giver.moles.len());
+		for (a, b) in self.moles.iter_mut().zip(giver.moles.iter()) {
+			*a += b * ratio;
+		}
+		let combined_heat_capacity = our_heat_capacity + other_heat_capacity;
 		if combined_heat_capacity > MINIMUM_HEAT_CAPACITY {
 			self.set_temperature(
 				(our_heat_capacity * self.temperature + other_heat_capacity * giver.temperature)
 					/ (combined_heat_capacity),
 			);
 		}
-		self.cached_heat_capacity.set(combined_heat_capacity);
+		self.cache_merged_heat_capacity(giver, combined_heat_capacity);
+		self.cached_total_moles.invalidate();
+		self.cached_presence.invalidate();
 	}
 	/// Turns a gas mixture into the weighted average of us and the giver, with the weights being (1-ratio, ratio), for self and the giver respectively.
-	pub fn share_ratio(&mut self, giver: &Self, r: f32) {
+	/// Takes the sim floors from the caller, who should read them once for however many mixes it's sharing.
+	pub fn share_ratio(&mut self, giver: &Self, r: f32, floors: SimFloors) {
 		if self.immutable {
 			return;
 		}
 		let ratio = r.clamp(0.0, 1.0);
-		self.multiply(1.0 - ratio);
+		self.multiply_with_floors(1.0 - ratio, floors);
 		let our_heat_capacity = self.heat_capacity();
 		let other_heat_capacity = giver.heat_capacity() * ratio;
 		self.maybe_expand(giver.moles.len());
@@ -299,31 +1071,87 @@ impl Mixture {
 		}
 		let combined_heat_capacity = our_heat_capacity + other_heat_capacity;
 		if combined_heat_capacity > MINIMUM_HEAT_CAPACITY {
-			self.set_temperature(
+			self.set_temperature_with_floors(
 				(our_heat_capacity * self.temperature + other_heat_capacity * giver.temperature)
 					/ (combined_heat_capacity),
+				floors,
 			);
 		}
-		self.cached_heat_capacity.set(combined_heat_capacity);
+		self.cache_merged_heat_capacity(giver, combined_heat_capacity);
+		self.cached_total_moles.invalidate();
+		self.cached_presence.invalidate();
+	}
+	/// Moves each gas amount and the temperature `t` of the way towards the target's, with `t` between 0 and 1.
+	/// Gases only in the target are added proportionally, gases only in us decay towards zero.
+	pub fn lerp_towards(&mut self, target: &Self, t: f32) {
+		if self.immutable {
+			return;
+		}
+		let t = t.clamp(0.0, 1.0);
+		self.maybe_expand(target.moles.len());
+		for (i, amt) in self.moles.iter_mut().enumerate() {
+			*amt += (target.get_moles(i) - *amt) * t;
+		}
+		self.set_temperature(self.temperature + (target.temperature - self.temperature) * t);
+		self.cached_heat_capacity.invalidate();
+		self.cached_total_moles.invalidate();
+		self.cached_presence.invalidate();
+		self.garbage_collect();
 	}
 	/// Transfers only the given gases from us to another mix.
 	pub fn transfer_gases_to(&mut self, r: f32, gases: &[GasIDX], into: &mut Self) {
-		let ratio = r.clamp(0.0, 1.0);
+		self.scrub_into(gases, into, r);
+	}
+	/// Moves up to `moles` of one gas from us into another mix, along with its thermal energy, as `scrub_into` does.
+	/// Returns the moles actually moved, which is all we have of it if that's less, or nothing if `dest` is immutable.
+	pub fn transfer_gas_to(&mut self, gas: GasIDX, moles: f32, dest: &mut Self) -> f32 {
+		let available = self.get_moles(gas);
+		if dest.immutable || available <= 0.0 || moles.is_nan() || moles <= 0.0 {
+			return 0.0;
+		}
+		let moved = moles.min(available);
+		self.scrub_into(&[gas], dest, moved / available);
+		moved
+	}
+	/// Moves `ratio` of each of the given gases from us into another mix, along with their thermal energy.
+	/// Unlisted gases are left untouched, and listed gases we don't have are skipped.
+	/// If we're immutable, the gases are copied over without being removed. If `into` is immutable, nothing happens.
+	pub fn scrub_into(&mut self, gases: &[GasIDX], into: &mut Self, ratio: f32) {
+		if into.immutable {
+			return;
+		}
+		let ratio = ratio.clamp(0.0, 1.0);
 		let initial_energy = into.thermal_energy();
 		let mut heat_transfer = 0.0;
-		with_specific_heats(|heats| {
+		let mut transferred: Vec<(GasIDX, f32)> = Vec::with_capacity(gases.len());
+		with_overridden_specific_heats(&self.heat_capacity_overrides, |heats| {
 			for i in gases.iter().copied() {
 				if let Some(orig) = self.moles.get_mut(i) {
 					let delta = *orig * ratio;
-					heat_transfer += delta * self.temperature * heats[i];
-					*orig -= delta;
-					into.adjust_moles(i, delta);
+					if delta > 0.0 {
+						heat_transfer += delta * self.temperature * heats[i];
+						if !self.immutable {
+							*orig -= delta;
+						}
+						transferred.push((i, delta));
+					}
 				}
 			}
 		});
-		self.cached_heat_capacity.invalidate();
-		into.cached_heat_capacity.invalidate();
-		into.set_temperature((initial_energy + heat_transfer) / into.heat_capacity());
+		if transferred.is_empty() {
+			return;
+		}
+		if !self.immutable {
+			self.cached_heat_capacity.invalidate();
+			self.cached_total_moles.invalidate();
+			self.cached_presence.invalidate();
+			self.garbage_collect();
+		}
+		into.adjust_multi(&transferred);
+		let into_heat_capacity = into.heat_capacity();
+		if into_heat_capacity > MINIMUM_HEAT_CAPACITY {
+			into.set_temperature((initial_energy + heat_transfer) / into_heat_capacity);
+		}
 	}
 	/// Takes a percentage of this gas mixture's moles and puts it into another mixture. if this mix is mutable, also removes those moles from the original.
 	pub fn remove_ratio_into(&mut self, mut ratio: f32, into: &mut Self) {
@@ -348,10 +1176,156 @@ impl Mixture {
 		self.remove_ratio_into(ratio, &mut removed);
 		removed
 	}
-	/// Like `remove_ratio`, but with moles.
+	/// Like `remove_ratio`, but the removed gas comes out at the minimum temperature and the rest of its thermal energy stays here,
+	/// so what's left heats up as its heat capacity drops. Total thermal energy is kept, and the temperature isn't capped.
+	/// This deliberately breaks the usual rule of heat following the gas it's in; it's for things like cold traps,
+	/// which strip gas out but not its heat.
+	#[must_use]
+	pub fn remove_ratio_keep_heat(&mut self, ratio: f32) -> Self {
+		let energy = self.thermal_energy();
+		let mut removed = self.remove_ratio(ratio);
+		removed.set_temperature(sim_floors().min_temperature);
+		let cap = self.heat_capacity();
+		if cap >= MINIMUM_HEAT_CAPACITY {
+			self.set_temperature((energy - removed.thermal_energy()) / cap);
+		}
+		removed
+	}
+	/// Takes a breath of the given volume: that volume's share of every gas, at our temperature, returned as a mix of that volume.
+	/// Pair with `exhale` to give back what the lungs made.
+	#[must_use]
+	pub fn breathe(&mut self, breath_volume: f32) -> Self {
+		let mut breath = self.remove_ratio(breath_volume / self.volume);
+		breath.volume = breath_volume;
+		breath
+	}
+	/// Adds the given moles of CO2 at our temperature, as breathed out after `breathe`.
+	/// # Errors
+	/// If CO2 isn't a registered gas.
+	pub fn exhale(&mut self, co2_moles: f32) -> Result<(), auxtools::Runtime> {
+		self.adjust_moles(gas_idx_from_string(GAS_CO2)?, co2_moles);
+		Ok(())
+	}
+	/// Moves gas from us into the output until the output reaches the target pressure or we run out.
+	/// Uses the output's temperature to work out how many moles that takes, or ours if the output is empty.
+	/// Returns the amount of moles moved.
+	pub fn pump_to_pressure(&mut self, output: &mut Self, target_pressure: f32) -> f32 {
+		let pressure_delta = target_pressure - output.return_pressure();
+		if pressure_delta.is_nan() || pressure_delta <= 0.0 {
+			return 0.0;
+		}
+		let temperature = if output.is_empty() {
+			self.temperature
+		} else {
+			output.temperature
+		};
+		let moles = (pressure_delta * output.volume / (temperature * R_IDEAL_GAS_EQUATION))
+			.min(self.total_moles());
+		if moles.is_nan() || moles <= 0.0 {
+			return 0.0;
+		}
+		output.merge(&self.remove(moles));
+		moles
+	}
+	/// Moves gas from whichever of us and the other mix is at the higher pressure into the other one,
+	/// just enough for both to end up at the same pressure, keeping total moles and thermal energy.
+	/// The gas moved is a slice of the source's, at its temperature, so the source keeps its makeup and temperature
+	/// and only the destination's change. Does nothing if either mix is immutable, both are empty
+	/// or their pressures are already within `MINIMUM_PRESSURE_DELTA_TO_EQUALIZE`.
+	pub fn equalize_pressure_with(&mut self, other: &mut Self) {
+		if self.immutable
+			|| other.immutable
+			|| (self.is_empty() && other.is_empty())
+			|| self.volume.is_nan()
+			|| other.volume.is_nan()
+			|| self.volume <= 0.0
+			|| other.volume <= 0.0
+			|| (self.return_pressure() - other.return_pressure()).abs()
+				< MINIMUM_PRESSURE_DELTA_TO_EQUALIZE
+		{
+			return;
+		}
+		let (source, dest) = if self.return_pressure() > other.return_pressure() {
+			(self, other)
+		} else {
+			(other, self)
+		};
+		let ratio = source.pressure_equalizing_ratio(dest);
+		if ratio > 0.0 {
+			let moved = source.remove_ratio(ratio);
+			dest.merge(&moved);
+		}
+	}
+	// The share of our moles that, moved into the lower pressure dest at our temperature, leaves both at the same pressure.
+	// With x moles moved, we're at (n_s - x) * T_s / V_s and the dest at (n_d + x) * T_d' / V_d, scaled by R,
+	// where T_d' = (E_d + c_s * x * T_s) / (C_d + c_s * x), c_s being our heat capacity per mole.
+	// Setting them equal gives a quadratic in x with exactly one root between 0 and n_s.
+	fn pressure_equalizing_ratio(&self, dest: &Self) -> f32 {
+		let source_moles = f64::from(self.total_moles());
+		if source_moles <= 0.0 {
+			return 0.0;
+		}
+		let source_temp = f64::from(self.temperature);
+		let source_volume = f64::from(self.volume);
+		let dest_moles = f64::from(dest.total_moles());
+		let dest_heat_capacity = f64::from(dest.heat_capacity());
+		let dest_energy = dest_heat_capacity * f64::from(dest.temperature);
+		let dest_volume = f64::from(dest.volume);
+		let molar_heat_capacity = f64::from(self.heat_capacity()) / source_moles;
+		let a = -molar_heat_capacity * source_temp * (source_volume + dest_volume);
+		let b =
+			source_temp * dest_volume * (source_moles * molar_heat_capacity - dest_heat_capacity)
+				- source_volume * (dest_moles * molar_heat_capacity * source_temp + dest_energy);
+		let c = source_temp * dest_volume * source_moles * dest_heat_capacity
+			- source_volume * dest_moles * dest_energy;
+		let moved = if a.abs() > f64::EPSILON {
+			(b + (b * b - 4.0 * a * c).max(0.0).sqrt()) / (-2.0 * a)
+		} else if b.abs() > f64::EPSILON {
+			-c / b
+		} else {
+			0.0
+		};
+		(moved / source_moles).clamp(0.0, 1.0) as f32
+	}
+	/// Lets gas flow from us into the output, like a canister's release valve: it stops once the output reaches `release_pressure`,
+	/// or once the two are at the same pressure, whichever comes first, taking both volumes into account.
+	/// Unlike `pump_to_pressure`, this never pushes gas into a higher pressure. Returns the amount of moles released.
+	pub fn release_to(&mut self, output: &mut Self, release_pressure: f32) -> f32 {
+		let input_pressure = self.return_pressure();
+		let output_pressure = output.return_pressure();
+		if release_pressure.is_nan()
+			|| output_pressure >= release_pressure
+			|| output_pressure >= input_pressure
+			|| self.temperature <= 0.0
+		{
+			return 0.0;
+		}
+		let moles_per_pressure = 1.0 / (self.temperature * R_IDEAL_GAS_EQUATION);
+		// where both end up at the same pressure, assuming the released gas stays at our temperature
+		let to_equilibrium = (input_pressure - output_pressure) * moles_per_pressure
+			/ (1.0 / self.volume + 1.0 / output.volume);
+		let to_release_pressure =
+			(release_pressure - output_pressure) * output.volume * moles_per_pressure;
+		let moles = to_equilibrium
+			.min(to_release_pressure)
+			.min(self.total_moles());
+		if moles.is_nan() || moles <= 0.0 {
+			return 0.0;
+		}
+		output.merge(&self.remove(moles));
+		moles
+	}
+	/// Like `remove_ratio`, but with moles. The removed gas is spread proportionally over every gas, at our temperature.
+	/// Removes everything if we have less than that; if we're empty, the returned mix is too.
 	#[must_use]
 	pub fn remove(&mut self, amount: f32) -> Self {
-		self.remove_ratio(amount / self.total_moles())
+		let total_moles = self.total_moles();
+		if total_moles <= 0.0 || amount.is_nan() || amount <= 0.0 {
+			let mut removed = Self::from_vol(self.volume);
+			removed.set_temperature(self.temperature);
+			return removed;
+		}
+		self.remove_ratio(amount / total_moles)
 	}
 	/// Copies from a given gas mixture, if we're mutable.
 	pub fn copy_from_mutable(&mut self, sample: &Self) {
@@ -360,7 +1334,80 @@ impl Mixture {
 		}
 		self.moles = sample.moles.clone();
 		self.temperature = sample.temperature;
-		self.cached_heat_capacity = sample.cached_heat_capacity.clone();
+		if self.heat_capacity_overrides == sample.heat_capacity_overrides {
+			self.cached_heat_capacity = sample.cached_heat_capacity.clone();
+		} else {
+			self.cached_heat_capacity.invalidate();
+		}
+		self.cached_total_moles = sample.cached_total_moles.clone();
+		self.cached_presence = sample.cached_presence.clone();
+	}
+	/// Swaps our gases and temperature, and so our thermal energy, with the other mix's. Volumes stay where they are,
+	/// so each mix's pressure changes to suit its own volume. Does nothing if either mix is immutable.
+	pub fn swap_contents(&mut self, other: &mut Self) {
+		if self.immutable || other.immutable {
+			return;
+		}
+		std::mem::swap(&mut self.moles, &mut other.moles);
+		std::mem::swap(&mut self.temperature, &mut other.temperature);
+		std::mem::swap(&mut self.cached_total_moles, &mut other.cached_total_moles);
+		std::mem::swap(&mut self.cached_presence, &mut other.cached_presence);
+		if self.heat_capacity_overrides == other.heat_capacity_overrides {
+			std::mem::swap(
+				&mut self.cached_heat_capacity,
+				&mut other.cached_heat_capacity,
+			);
+		} else {
+			self.cached_heat_capacity.invalidate();
+			other.cached_heat_capacity.invalidate();
+		}
+	}
+	/// Replaces our gases and temperature, and volume too if `copy_volume` is set, with copies of the source's.
+	/// Whether reactions are suppressed is copied as well.
+	/// Heat capacity is recached once, at the end, using our own heat capacity overrides.
+	pub fn copy_from(&mut self, source: &Self, copy_volume: bool) {
+		if self.immutable {
+			return;
+		}
+		self.moles.clone_from(&source.moles);
+		self.temperature = source.temperature;
+		if copy_volume {
+			self.volume = source.volume;
+		}
+		self.reactions_suppressed = source.reactions_suppressed;
+		self.cached_total_moles = source.cached_total_moles.clone();
+		self.cached_presence = source.cached_presence.clone();
+		self.cached_heat_capacity.invalidate();
+		self.heat_capacity();
+	}
+	/// Makes a new mix with however many more moles of each gas we have than the other mix, at our temperature and volume.
+	/// Gases the other mix has more of come out as zero rather than negative, since negative moles don't exist.
+	#[must_use]
+	pub fn difference(&self, other: &Self) -> Self {
+		let mut delta = Self::from_vol(self.volume);
+		delta.moles = self
+			.moles
+			.iter()
+			.enumerate()
+			.map(|(idx, &amt)| (amt - other.get_moles(idx)).max(0.0))
+			.collect();
+		delta.temperature = self.temperature;
+		delta.garbage_collect();
+		delta
+	}
+	/// Makes a new mix out of `a_ratio` of `a`'s gas and the rest of `b`'s, at the temperature their combined thermal energy gives.
+	/// The ratio is clamped between 0 and 1. The new mix's volume is the given one, or the larger of the two.
+	#[must_use]
+	pub fn blend(a: &Self, b: &Self, a_ratio: f32, volume: Option<f32>) -> Self {
+		let a_ratio = a_ratio.clamp(0.0, 1.0);
+		let mut blended = Self::from_vol(volume.unwrap_or_else(|| a.volume.max(b.volume)));
+		let mut a_part = a.copy_to_mutable();
+		a_part.multiply(a_ratio);
+		blended.merge(&a_part);
+		let mut b_part = b.copy_to_mutable();
+		b_part.multiply(1.0 - a_ratio);
+		blended.merge(&b_part);
+		blended
 	}
 	/// Makes a copy of this gas mixture that is guaranteed mutable, regardless of whether this one is immutable
 	pub fn copy_to_mutable(&self) -> Self {
@@ -372,6 +1419,9 @@ impl Mixture {
 	/// Works well enough for our purposes, though perhaps called less often
 	/// than it ought to be while we're working in Rust.
 	/// Differs from the original by not using archive, since we don't put the archive into the gas mix itself anymore.
+	/// Otherwise it's LINDA's equation: `heat = coefficient * (T_self - T_sharer) * (C_self * C_sharer / (C_self + C_sharer))`,
+	/// taken from us and given to the sharer. Turf heat conduction uses this too, so devices calling it get the same results.
+	/// Returns the sharer's new temperature.
 	pub fn temperature_share(&mut self, sharer: &mut Self, conduction_coefficient: f32) -> f32 {
 		let temperature_delta = self.temperature - sharer.temperature;
 		if temperature_delta.abs() > MINIMUM_TEMPERATURE_DELTA_TO_CONSIDER {
@@ -385,17 +1435,38 @@ impl Mixture {
 					* temperature_delta * (self_heat_capacity * sharer_heat_capacity
 					/ (self_heat_capacity + sharer_heat_capacity));
 				if !self.immutable {
-					self.set_temperature((self.temperature - heat / self_heat_capacity).max(TCMB));
+					self.set_temperature(self.temperature - heat / self_heat_capacity);
 				}
 				if !sharer.immutable {
-					sharer.set_temperature(
-						(sharer.temperature + heat / sharer_heat_capacity).max(TCMB),
-					);
+					sharer.set_temperature(sharer.temperature + heat / sharer_heat_capacity);
 				}
 			}
 		}
 		sharer.temperature
 	}
+	/// Moves heat between the two mixes towards their shared equilibrium temperature without moving any gas.
+	/// `conductivity` is clamped to between 0 and 1; at 1, both mixes end up at the equilibrium temperature.
+	pub fn thermal_equalize_with(&mut self, other: &mut Self, conductivity: f32) {
+		let conductivity = conductivity.clamp(0.0, 1.0);
+		let self_heat_capacity = self.heat_capacity();
+		let other_heat_capacity = other.heat_capacity();
+		if conductivity > 0.0
+			&& self_heat_capacity > MINIMUM_HEAT_CAPACITY
+			&& other_heat_capacity > MINIMUM_HEAT_CAPACITY
+		{
+			let equilibrium_temperature = (self.temperature * self_heat_capacity
+				+ other.temperature * other_heat_capacity)
+				/ (self_heat_capacity + other_heat_capacity);
+			let self_temperature = self.temperature;
+			let other_temperature = other.temperature;
+			self.set_temperature(
+				self_temperature + (equilibrium_temperature - self_temperature) * conductivity,
+			);
+			other.set_temperature(
+				other_temperature + (equilibrium_temperature - other_temperature) * conductivity,
+			);
+		}
+	}
 	/// As above, but you may put in any arbitrary coefficient, temp, heat capacity.
 	/// Only used for superconductivity as of right now.
 	pub fn temperature_share_non_gas(
@@ -415,9 +1486,10 @@ impl Mixture {
 					* temperature_delta * (self_heat_capacity * sharer_heat_capacity
 					/ (self_heat_capacity + sharer_heat_capacity));
 				if !self.immutable {
-					self.set_temperature((self.temperature - heat / self_heat_capacity).max(TCMB));
+					self.set_temperature(self.temperature - heat / self_heat_capacity);
 				}
-				return (sharer_temperature + heat / sharer_heat_capacity).max(TCMB);
+				return (sharer_temperature + heat / sharer_heat_capacity)
+					.max(sim_floors().min_temperature);
 			}
 		}
 		sharer_temperature
@@ -436,6 +1508,38 @@ impl Mixture {
 			.zip_longest(sample.moles.iter().copied())
 			.fold(0.0, |acc, pair| acc.max(pair.reduce(|a, b| (b - a).abs())))
 	}
+	/// Returns the biggest difference between the two mixes: the gas with the biggest mole delta and that delta,
+	/// or `None` and the temperature delta if the temperature differs more, relative to `MINIMUM_TEMPERATURE_DELTA_TO_SUSPEND`
+	/// and `MINIMUM_MOLES_DELTA_TO_MOVE` respectively.
+	pub fn max_difference(&self, sample: &Self) -> (Option<GasIDX>, f32) {
+		let (gas_idx, moles_delta) = self
+			.moles
+			.iter()
+			.zip_longest(sample.moles.iter())
+			.map(|pair| match pair {
+				Both(a, b) => (a - b).abs(),
+				Left(a) | Right(a) => a.abs(),
+			})
+			.enumerate()
+			.fold(
+				(0, 0.0),
+				|best, (idx, delta)| {
+					if delta > best.1 {
+						(idx, delta)
+					} else {
+						best
+					}
+				},
+			);
+		let temperature_delta = (self.temperature - sample.temperature).abs();
+		if temperature_delta / MINIMUM_TEMPERATURE_DELTA_TO_SUSPEND
+			> moles_delta / MINIMUM_MOLES_DELTA_TO_MOVE
+		{
+			(None, temperature_delta)
+		} else {
+			(Some(gas_idx), moles_delta)
+		}
+	}
 	pub fn compare_with(&self, sample: &Self, amt: f32) -> bool {
 		self.moles
 			.as_slice()
@@ -453,6 +1557,8 @@ impl Mixture {
 		if !self.immutable {
 			self.moles.clear();
 			self.cached_heat_capacity.invalidate();
+			self.cached_total_moles.invalidate();
+			self.cached_presence.invalidate();
 		}
 	}
 	/// Resets the gas mixture to an initialized-with-volume state.
@@ -460,17 +1566,53 @@ impl Mixture {
 		self.temperature = 2.7;
 		self.volume = vol;
 		self.min_heat_capacity = 0.0;
+		self.heat_capacity_overrides.clear();
+		self.archived = None;
+		self.history = None;
+		self.last_reacted.clear();
+		self.reactions_suppressed = false;
 		self.immutable = false;
 		self.clear();
 	}
+	/// Moves the mix's pressure toward the target by at most `max_delta` kilopascals, for cycling airlocks over several ticks.
+	/// Every gas is scaled by the same factor at the same temperature, so the mix keeps its makeup: gas taken out is deleted,
+	/// and gas put in is made from nothing, as if from an endless reservoir of this same mix. Neither is conserved.
+	/// An empty mix has nothing to scale, so it can't be brought up from zero.
+	/// Returns whether the mix is now at the target pressure.
+	pub fn step_toward_pressure(&mut self, target_pressure: f32, max_delta: f32) -> bool {
+		let pressure = self.return_pressure();
+		let target_pressure = target_pressure.max(0.0);
+		let difference = target_pressure - pressure;
+		if difference == 0.0 {
+			return true;
+		}
+		if self.immutable || pressure <= 0.0 {
+			return false;
+		}
+		let max_delta = max_delta.max(0.0);
+		let reached = difference.abs() <= max_delta;
+		let new_pressure = if reached {
+			target_pressure
+		} else {
+			pressure + max_delta.copysign(difference)
+		};
+		self.multiply(new_pressure / pressure);
+		reached
+	}
 	/// Multiplies every gas molage with this value.
 	pub fn multiply(&mut self, multiplier: f32) {
+		self.multiply_with_floors(multiplier, sim_floors());
+	}
+	/// Like `multiply`, against floors the caller has already read.
+	pub fn multiply_with_floors(&mut self, multiplier: f32, floors: SimFloors) {
 		if !self.immutable {
 			for amt in self.moles.iter_mut() {
 				*amt *= multiplier;
 			}
 			self.cached_heat_capacity.invalidate();
-			self.garbage_collect();
+			self.cached_total_moles.invalidate();
+			self.cached_presence.invalidate();
+			self.garbage_collect_with_floors(floors);
 		}
 	}
 	pub fn add(&mut self, num: f32) {
@@ -479,18 +1621,17 @@ impl Mixture {
 				*amt += num;
 			}
 			self.cached_heat_capacity.invalidate();
+			self.cached_total_moles.invalidate();
+			self.cached_presence.invalidate();
 			self.garbage_collect();
 		}
 	}
-	pub fn can_react_with_reactions(
-		&self,
-		reactions: &BTreeMap<ReactionPriority, Reaction>,
-	) -> bool {
+	pub fn can_react_with_reactions(&self, reactions: &BTreeMap<ReactionOrder, Reaction>) -> bool {
+		let present = self.presence_mask();
 		//priorities are inversed because fuck you
-		reactions
-			.values()
-			.rev()
-			.any(|reaction| reaction.check_conditions(self))
+		reactions.values().rev().any(|reaction| {
+			reaction.has_required_gases(&present) && reaction.check_conditions(self)
+		})
 	}
 	/// Checks if the proc can react with any reactions.
 	pub fn can_react(&self) -> bool {
@@ -498,41 +1639,98 @@ impl Mixture {
 	}
 	pub fn all_reactable_with_slice(
 		&self,
-		reactions: &BTreeMap<ReactionPriority, Reaction>,
+		reactions: &BTreeMap<ReactionOrder, Reaction>,
 	) -> TinyVec<[u64; MAX_REACTION_TINYVEC_SIZE]> {
+		let present = self.presence_mask();
 		//priorities are inversed because fuck you
 		reactions
 			.values()
 			.rev()
-			.filter_map(|thin| thin.check_conditions(self).then(|| thin.get_id()))
+			.filter_map(|thin| {
+				(thin.has_required_gases(&present) && thin.check_conditions(self))
+					.then(|| thin.get_id())
+			})
 			.collect()
 	}
+	/// Whether it's been at least `cooldown` reaction ticks since the given reaction last reacted in this mix.
+	pub fn reaction_off_cooldown(&self, id: ReactionIdentifier, cooldown: u32, tick: u32) -> bool {
+		self.last_reacted
+			.get(&id)
+			.map_or(true, |&last| tick.wrapping_sub(last) >= cooldown)
+	}
+	/// Records that the given reaction reacted in this mix on the given reaction tick.
+	pub fn set_reacted(&mut self, id: ReactionIdentifier, tick: u32) {
+		self.last_reacted.insert(id, tick);
+	}
+	/// Adds to the moles of fuel fires burned in this mix this reaction tick, counting from zero again on a new tick.
+	pub fn record_fuel_burnt(&mut self, moles: f32) {
+		let tick = current_reaction_tick();
+		self.fuel_burnt = Some(match self.fuel_burnt {
+			Some((last_tick, burnt, before)) if last_tick == tick => (tick, burnt + moles, before),
+			Some((last_tick, burnt, _)) if last_tick.wrapping_add(1) == tick => {
+				(tick, moles, burnt)
+			}
+			_ => (tick, moles, 0.0),
+		});
+	}
+	/// The moles of fuel fires burned in this mix during the last finished reaction tick; zero if nothing burned in it.
+	/// Doesn't change while the current tick's fires are still burning, so gauges don't drop to zero at the start of every tick.
+	pub fn get_fuel_burnt_last_tick(&self) -> f32 {
+		let tick = current_reaction_tick();
+		match self.fuel_burnt {
+			Some((last_tick, _, before)) if last_tick == tick => before,
+			Some((last_tick, burnt, _)) if last_tick.wrapping_add(1) == tick => burnt,
+			_ => 0.0,
+		}
+	}
 	/// Gets all of the reactions this mix should do.
 	pub fn all_reactable(&self) -> TinyVec<[u64; MAX_REACTION_TINYVEC_SIZE]> {
 		with_reactions(|reactions| self.all_reactable_with_slice(reactions))
 	}
+	/// Gets all of the reactions this mix should do in the given environment.
+	pub fn all_reactable_in_context(
+		&self,
+		env: &ReactionEnv,
+	) -> TinyVec<[u64; MAX_REACTION_TINYVEC_SIZE]> {
+		let present = self.presence_mask();
+		with_reactions(|reactions| {
+			//priorities are inversed because fuck you
+			reactions
+				.values()
+				.rev()
+				.filter_map(|thin| {
+					(thin.has_required_gases(&present) && thin.check_conditions_in(self, env))
+						.then(|| thin.get_id())
+				})
+				.collect()
+		})
+	}
 	/// Returns a tuple with oxidation power and fuel amount of this gas mixture.
 	pub fn get_burnability(&self) -> (f32, f32) {
+		self.get_burnability_at(self.temperature)
+	}
+	/// Like `get_burnability`, but as if the mix were at the given temperature.
+	/// Every gas registered as an oxidizer or fuel counts, weighted by its own fire temperature and power or burn rate.
+	pub fn get_burnability_at(&self, temp: f32) -> (f32, f32) {
 		use crate::types::FireInfo;
+		let min_moles = sim_floors().min_moles;
 		super::with_gas_info(|gas_info| {
 			self.moles
 				.iter()
 				.zip(gas_info)
 				.fold((0.0, 0.0), |mut acc, (&amt, this_gas_info)| {
-					if amt > GAS_MIN_MOLES {
+					if amt > min_moles {
 						match this_gas_info.fire_info {
 							FireInfo::Oxidation(oxidation) => {
-								if self.temperature > oxidation.temperature() {
-									let amount = amt
-										* (1.0 - oxidation.temperature() / self.temperature)
-											.max(0.0);
+								if temp > oxidation.temperature() {
+									let amount =
+										amt * (1.0 - oxidation.temperature() / temp).max(0.0);
 									acc.0 += amount * oxidation.power();
 								}
 							}
 							FireInfo::Fuel(fire) => {
-								if self.temperature > fire.temperature() {
-									let amount = amt
-										* (1.0 - fire.temperature() / self.temperature).max(0.0);
+								if temp > fire.temperature() {
+									let amount = amt * (1.0 - fire.temperature() / temp).max(0.0);
 									acc.1 += amount / fire.burn_rate();
 								}
 							}
@@ -543,13 +1741,13 @@ impl Mixture {
 				})
 		})
 	}
-	/// Returns only the oxidation power. Since this calculates burnability anyway, prefer `get_burnability`.
-	pub fn get_oxidation_power(&self) -> f32 {
-		self.get_burnability().0
+	/// Returns only the oxidation power at the given temperature. Since this calculates burnability anyway, prefer `get_burnability_at`.
+	pub fn get_oxidation_power(&self, temp: f32) -> f32 {
+		self.get_burnability_at(temp).0
 	}
-	/// Returns only fuel amount. Since this calculates burnability anyway, prefer `get_burnability`.
-	pub fn get_fuel_amount(&self) -> f32 {
-		self.get_burnability().1
+	/// Returns only fuel amount at the given temperature. Since this calculates burnability anyway, prefer `get_burnability_at`.
+	pub fn get_fuel_amount(&self, temp: f32) -> f32 {
+		self.get_burnability_at(temp).1
 	}
 	/// Like `get_fire_info`, but takes a reference to a gas info vector,
 	/// so one doesn't need to do a recursive lock on the global list.
@@ -558,12 +1756,13 @@ impl Mixture {
 		gas_info: &[super::GasType],
 	) -> (Vec<SpecificFireInfo>, Vec<SpecificFireInfo>) {
 		use crate::types::FireInfo;
+		let min_moles = sim_floors().min_moles;
 		self.moles
 			.iter()
 			.zip(gas_info)
 			.enumerate()
 			.filter_map(|(i, (&amt, this_gas_info))| {
-				(amt > GAS_MIN_MOLES)
+				(amt > min_moles)
 					.then(|| match this_gas_info.fire_info {
 						FireInfo::Oxidation(oxidation) => (self.get_temperature()
 							> oxidation.temperature())
@@ -591,11 +1790,23 @@ impl Mixture {
 	pub fn get_fire_info(&self) -> (Vec<SpecificFireInfo>, Vec<SpecificFireInfo>) {
 		super::with_gas_info(|gas_info| self.get_fire_info_with_lock(gas_info))
 	}
-	/// Adds heat directly to the gas mixture, in joules (probably).
+	/// Adds heat directly to the gas mixture, in joules. Negative values remove heat.
+	/// Won't cool the mix below the minimum temperature, and does nothing to a mix without any heat capacity.
 	pub fn adjust_heat(&mut self, heat: f32) {
 		let cap = self.heat_capacity();
+		if cap < MINIMUM_HEAT_CAPACITY || !heat.is_finite() {
+			return;
+		}
 		self.set_temperature(((cap * self.temperature) + heat) / cap);
 	}
+	/// Returns the gases that are at or above their visibility threshold, given as gas-moles pairs.
+	pub fn visible_gases(&self, thresholds: &[(GasIDX, f32)]) -> Vec<GasIDX> {
+		thresholds
+			.iter()
+			.filter(|&&(idx, amt)| self.get_moles(idx) >= amt)
+			.map(|&(idx, _)| idx)
+			.collect()
+	}
 	/// Returns true if there's a visible gas in this mix.
 	pub fn is_visible(&self) -> bool {
 		self.enumerate()
@@ -615,6 +1826,263 @@ impl Mixture {
 		}
 		hasher.finish()
 	}
+	/// A deterministic hash of the mix's gases and temperature, for deduplication and caching.
+	/// Moles and temperature are quantized first, so mixes that only differ by noise smaller
+	/// than `CONTENT_HASH_MOLES_EPSILON` and `CONTENT_HASH_TEMPERATURE_EPSILON` hash identically.
+	pub fn content_hash(&self) -> u64 {
+		use std::hash::Hasher;
+		let mut hasher = fxhash::FxHasher64::default();
+		for (i, gas_amt) in self.enumerate() {
+			let quantized = (gas_amt / CONTENT_HASH_MOLES_EPSILON).round() as i64;
+			if quantized != 0 {
+				hasher.write_usize(i);
+				hasher.write_i64(quantized);
+			}
+		}
+		hasher.write_i64((self.temperature / CONTENT_HASH_TEMPERATURE_EPSILON).round() as i64);
+		hasher.finish()
+	}
+	/// Zeroes every gas with less than `epsilon` moles, then snaps the temperature onto the grid
+	/// `content_hash` quantizes it to, so floating point noise can't build up on mixes that are otherwise at rest.
+	/// Moles are left as they are; `content_hash` already rounds away their noise.
+	/// A normalized mix hashes the same every tick until something actually changes it.
+	pub fn normalize(&mut self, epsilon: f32) {
+		self.normalize_with_floors(epsilon, sim_floors());
+	}
+	/// Like `normalize`, against floors the caller has already read.
+	pub fn normalize_with_floors(&mut self, epsilon: f32, floors: SimFloors) {
+		if self.immutable {
+			return;
+		}
+		for amt in self.moles.iter_mut() {
+			if *amt < epsilon {
+				*amt = 0.0;
+			}
+		}
+		self.set_temperature_with_floors(self.snapped_temperature(floors), floors);
+		self.cached_heat_capacity.invalidate();
+		self.garbage_collect_with_floors(floors);
+	}
+	/// Whether `normalize` with the minimum moles as `epsilon` would change anything. Always false for immutable mixes.
+	#[cfg_attr(not(feature = "turf_processing"), allow(dead_code))]
+	pub fn needs_normalizing(&self, floors: SimFloors) -> bool {
+		!self.immutable
+			&& (self.has_traces(floors) || self.temperature != self.snapped_temperature(floors))
+	}
+	// The temperature normalize snaps us to.
+	fn snapped_temperature(&self, floors: SimFloors) -> f32 {
+		((self.temperature / CONTENT_HASH_TEMPERATURE_EPSILON).round()
+			* CONTENT_HASH_TEMPERATURE_EPSILON)
+			.max(floors.min_temperature)
+	}
+	/// Serializes the mix into a compact binary format, for persistence.
+	/// Layout, little-endian: version byte, volume, temperature, then a table of (gas index, string ID length, string ID)
+	/// for every gas in the mix, then the (gas index, moles) pairs themselves.
+	/// The string IDs let `from_bytes` remap the indices if the gas registry changed in the meantime.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let gases = self.serialized_gases();
+		let mut bytes = Vec::with_capacity(11 + gases.len() * 24);
+		bytes.push(MIXTURE_SERIALIZATION_VERSION);
+		bytes.extend_from_slice(&self.volume.to_le_bytes());
+		bytes.extend_from_slice(&self.temperature.to_le_bytes());
+		write_gas_table(gases.iter().map(|&(idx, _)| idx), &mut bytes);
+		write_gases(&gases, &mut bytes);
+		bytes
+	}
+	/// Deserializes a mix made by `to_bytes`. Gases that no longer exist are dropped.
+	/// # Errors
+	/// If the buffer is truncated, from an unknown version, or refers to gas indices missing from its own table.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, auxtools::Runtime> {
+		let mut reader = bytes;
+		let version = take_bytes(&mut reader, 1)?[0];
+		if version != MIXTURE_SERIALIZATION_VERSION {
+			return Err(auxtools::runtime!(
+				"Unknown serialized gas mixture version: {}",
+				version
+			));
+		}
+		let volume = take_volume(&mut reader)?;
+		let temperature = take_f32(&mut reader)?;
+		let remap = read_gas_table(&mut reader)?;
+		let entries = read_gases(&mut reader, &remap)?;
+		Ok(Self::from_parts(volume, temperature, &entries))
+	}
+	/// As `to_bytes`, but without the version byte or gas table, for formats that write one table for many mixes.
+	/// Layout: volume, temperature, then the (gas index, moles) pairs.
+	pub(crate) fn to_bytes_untabled(&self) -> Vec<u8> {
+		let gases = self.serialized_gases();
+		let mut bytes = Vec::with_capacity(10 + gases.len() * 6);
+		bytes.extend_from_slice(&self.volume.to_le_bytes());
+		bytes.extend_from_slice(&self.temperature.to_le_bytes());
+		write_gases(&gases, &mut bytes);
+		bytes
+	}
+	/// Reads a mix made by `to_bytes_untabled`, remapping its gases with a table read by `read_gas_table`.
+	/// # Errors
+	/// If the buffer is truncated or refers to gas indices missing from the table.
+	pub(crate) fn from_bytes_untabled(
+		reader: &mut &[u8],
+		remap: &[(u16, Option<GasIDX>)],
+	) -> Result<Self, auxtools::Runtime> {
+		let volume = take_volume(reader)?;
+		let temperature = take_f32(reader)?;
+		let entries = read_gases(reader, remap)?;
+		Ok(Self::from_parts(volume, temperature, &entries))
+	}
+	fn serialized_gases(&self) -> Vec<(GasIDX, f32)> {
+		let min_moles = sim_floors().min_moles;
+		self.enumerate()
+			.filter(|&(_, amt)| amt > min_moles)
+			.collect()
+	}
+	fn from_parts(volume: f32, temperature: f32, entries: &[(GasIDX, f32)]) -> Self {
+		let mut mix = Self::from_vol(volume);
+		mix.set_moles_bulk(entries);
+		mix.set_temperature(temperature);
+		mix
+	}
+	/// Exports the mix as human-readable JSON, in the form `{"volume":..,"temperature":..,"gases":{"o2":..}}`.
+	pub fn to_json(&self) -> String {
+		let min_moles = sim_floors().min_moles;
+		let gases = with_gas_info(|gas_info| {
+			self.enumerate()
+				.filter(|&(_, amt)| amt > min_moles)
+				.map(|(idx, amt)| (gas_info[idx].id.to_string(), serde_json::Value::from(amt)))
+				.collect::<serde_json::Map<_, _>>()
+		});
+		serde_json::json!({
+			"volume": self.volume,
+			"temperature": self.temperature,
+			"gases": gases,
+		})
+		.to_string()
+	}
+	/// Imports a mix from JSON made by `to_json`.
+	/// # Errors
+	/// If the JSON is malformed, has an unknown gas ID, or has any non-finite or negative numbers.
+	pub fn from_json(json: &str) -> Result<Self, auxtools::Runtime> {
+		let parsed: serde_json::Value = serde_json::from_str(json)
+			.map_err(|e| auxtools::runtime!("Failed to parse gas mixture JSON: {}", e))?;
+		let get_number = |value: Option<&serde_json::Value>, key: &str| {
+			value
+				.and_then(serde_json::Value::as_f64)
+				.map(|n| n as f32)
+				.filter(|n| n.is_finite() && *n >= 0.0)
+				.ok_or_else(|| {
+					auxtools::runtime!(
+						"Gas mixture JSON has a missing or invalid number for {}",
+						key
+					)
+				})
+		};
+		let volume = get_number(parsed.get("volume"), "volume")?;
+		if volume <= 0.0 {
+			return Err(auxtools::runtime!(
+				"Gas mixture JSON has a non-positive volume: {}",
+				volume
+			));
+		}
+		let temperature = get_number(parsed.get("temperature"), "temperature")?;
+		let gases = parsed
+			.get("gases")
+			.and_then(serde_json::Value::as_object)
+			.ok_or_else(|| auxtools::runtime!("Gas mixture JSON has no gases object!"))?;
+		let mut entries = Vec::with_capacity(gases.len());
+		for (id, amt) in gases {
+			let idx = gas_idx_from_string(id)
+				.map_err(|_| auxtools::runtime!("Unknown gas ID in gas mixture JSON: {}", id))?;
+			entries.push((idx, get_number(Some(amt), id)?));
+		}
+		let mut mix = Self::from_vol(volume);
+		mix.set_moles_bulk(&entries);
+		mix.set_temperature(temperature);
+		Ok(mix)
+	}
+	/// Whether every gas's moles are within `moles_tol` of the other mix's, and the temperatures within `temp_tol`.
+	/// A gas only one mix has counts as a difference of its whole amount.
+	pub fn approx_eq(&self, other: &Self, moles_tol: f32, temp_tol: f32) -> bool {
+		(self.temperature - other.temperature).abs() <= temp_tol
+			&& self
+				.moles
+				.iter()
+				.zip_longest(other.moles.iter())
+				.all(|pair| match pair {
+					Both(a, b) => (a - b).abs() <= moles_tol,
+					Left(a) | Right(a) => a.abs() <= moles_tol,
+				})
+	}
+	/// Copies the moles of each gas in the mix, for `stats::record_difference` to see what changed.
+	/// Much cheaper than a snapshot, since it's only the gases the mix has.
+	pub fn moles_copy(&self) -> TinyVec<[f32; 8]> {
+		self.moles.clone()
+	}
+	/// Makes an owned copy of the mix's gases, temperature and volume, so expensive work can be done without holding the mix's lock.
+	pub fn snapshot(&self) -> MixtureSnapshot {
+		MixtureSnapshot {
+			temperature: self.temperature,
+			volume: self.volume,
+			heat_capacity: self.heat_capacity(),
+			moles: self.moles.as_slice().into(),
+		}
+	}
+	/// Saves the mix's gases, temperature and volume, to be brought back by `restore_archive`.
+	/// Replaces any previous archive.
+	pub fn archive(&mut self) {
+		self.archived = Some(self.snapshot());
+	}
+	/// Starts keeping the mix's last `limit` states, recorded every time it shares or reacts. 0 stops keeping them.
+	/// States already kept stay, as many of them as fit.
+	pub fn enable_history(&mut self, limit: usize) {
+		if limit == 0 {
+			self.history = None;
+		} else {
+			let history = self.history.get_or_insert_with(|| MixtureHistory {
+				limit,
+				snapshots: VecDeque::with_capacity(limit),
+			});
+			history.limit = limit;
+			while history.snapshots.len() > limit {
+				history.snapshots.pop_front();
+			}
+		}
+	}
+	/// Adds the mix's current state to its history, if it's keeping one, dropping the oldest state if it's full.
+	pub fn record_history(&mut self) {
+		if self.history.is_some() {
+			let snapshot = self.snapshot();
+			if let Some(history) = self.history.as_mut() {
+				if history.snapshots.len() >= history.limit {
+					history.snapshots.pop_front();
+				}
+				history.snapshots.push_back(snapshot);
+			}
+		}
+	}
+	/// The states in the mix's history, oldest first. Empty if it isn't keeping one.
+	pub fn history(&self) -> Vec<MixtureSnapshot> {
+		self.history.as_ref().map_or_else(Vec::new, |history| {
+			history.snapshots.iter().cloned().collect()
+		})
+	}
+	/// Reverts the mix to the last archive, if there is one and the mix isn't immutable.
+	/// Returns whether anything was restored. The archive is kept, so it can be restored again.
+	pub fn restore_archive(&mut self) -> bool {
+		if self.immutable {
+			return false;
+		}
+		match &self.archived {
+			Some(archive) => {
+				self.moles = archive.moles.iter().copied().collect();
+				self.temperature = archive.temperature;
+				self.volume = archive.volume;
+				self.cached_heat_capacity.invalidate();
+				self.cached_total_moles.invalidate();
+				self.cached_presence.invalidate();
+				true
+			}
+			None => false,
+		}
+	}
 	/// Compares the current vis hash to the provided one; returns true if they are
 	pub fn vis_hash_changed(
 		&self,
@@ -628,21 +2096,85 @@ impl Mixture {
 			})
 			.is_ok()
 	}
+	/// Whether `gc_traces` would remove anything: any gas with the minimum moles or less, or zeroes at the end.
+	/// Always false for immutable mixes.
+	pub fn has_traces(&self, floors: SimFloors) -> bool {
+		let min_moles = floors.min_moles;
+		!self.immutable
+			&& (self.moles.iter().any(|&amt| amt != 0.0 && amt <= min_moles)
+				|| (self.moles.len() > 1
+					&& self.moles.last().map_or(false, |&amt| amt <= min_moles)))
+	}
+	/// Removes every gas with the minimum moles or less, keeping the temperature as it is.
+	/// The heat lost with them is too small to matter. Returns whether anything was removed;
+	/// if there's nothing to remove, this doesn't touch the mix at all.
+	pub fn gc_traces(&mut self) -> bool {
+		let floors = sim_floors();
+		if !self.has_traces(floors) {
+			return false;
+		}
+		self.garbage_collect_with_floors(floors);
+		self.cached_heat_capacity.invalidate();
+		true
+	}
 	// Removes all redundant zeroes from the gas mixture.
 	pub fn garbage_collect(&mut self) {
+		self.garbage_collect_with_floors(sim_floors());
+	}
+	/// Like `garbage_collect`, against floors the caller has already read.
+	pub fn garbage_collect_with_floors(&mut self, floors: SimFloors) {
+		let min_moles = floors.min_moles;
 		let mut last_valid_found = 0;
 		for (i, amt) in self.moles.iter_mut().enumerate() {
-			if *amt > GAS_MIN_MOLES {
+			if *amt > min_moles {
 				last_valid_found = i;
 			} else {
 				*amt = 0.0;
 			}
 		}
 		self.moles.truncate(last_valid_found + 1);
+		self.cached_total_moles.invalidate();
+		self.cached_presence.invalidate();
+	}
+}
+
+// A mix's last few states, oldest first, for tracking down how it got the way it is.
+#[derive(Clone)]
+struct MixtureHistory {
+	limit: usize,
+	snapshots: VecDeque<MixtureSnapshot>,
+}
+
+/// A read-only copy of a gas mixture's gases, temperature and volume, detached from the arena.
+/// Cloning it is cheap, as the moles are shared.
+#[derive(Clone)]
+pub struct MixtureSnapshot {
+	pub temperature: f32,
+	pub volume: f32,
+	pub heat_capacity: f32,
+	moles: Arc<[f32]>,
+}
+
+impl MixtureSnapshot {
+	/// Returns moles of the given gas.
+	pub fn get_moles(&self, idx: GasIDX) -> f32 {
+		self.moles.get(idx).copied().unwrap_or(0.0)
+	}
+	/// Iterates over every gas index and its moles.
+	pub fn enumerate(&self) -> impl Iterator<Item = (GasIDX, f32)> + '_ {
+		self.moles.iter().copied().enumerate()
+	}
+	/// The total mole count of the snapshot. Moles.
+	pub fn total_moles(&self) -> f32 {
+		self.moles.iter().sum()
+	}
+	/// Pressure. Kilopascals.
+	pub fn return_pressure(&self) -> f32 {
+		self.total_moles() * R_IDEAL_GAS_EQUATION * self.temperature / self.volume
 	}
 }
 
-use std::ops::{Add, Mul};
+use std::ops::{Add, Mul, Sub};
 
 /// Takes a copy of the mix, merges the right hand side, then returns the copy.
 impl Add<&Mixture> for Mixture {
@@ -666,6 +2198,24 @@ impl<'a, 'b> Add<&'a Mixture> for &'b Mixture {
 	}
 }
 
+/// Returns the difference between the two mixes, see `Mixture::difference`.
+impl Sub<&Mixture> for Mixture {
+	type Output = Self;
+
+	fn sub(self, rhs: &Mixture) -> Self {
+		self.difference(rhs)
+	}
+}
+
+/// Returns the difference between the two mixes, see `Mixture::difference`.
+impl<'a, 'b> Sub<&'a Mixture> for &'b Mixture {
+	type Output = Mixture;
+
+	fn sub(self, rhs: &Mixture) -> Mixture {
+		self.difference(rhs)
+	}
+}
+
 /// Makes a copy of the given mix, multiplied by a scalar.
 impl Mul<f32> for Mixture {
 	type Output = Self;
@@ -769,4 +2319,343 @@ mod tests {
 		assert_eq!(new_two.get_moles(0), 5.5);
 		destroy_gas_statics();
 	}
+	#[test]
+	fn test_remove_ratio_keep_heat() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 100.0);
+		mix.set_temperature(300.0);
+		let energy = mix.thermal_energy();
+		let removed = mix.remove_ratio_keep_heat(0.5);
+		assert_eq!(removed.get_temperature(), sim_floors().min_temperature);
+		// the removed gas keeps what it has at the minimum temperature, the rest stays here
+		assert!(((mix.thermal_energy() + removed.thermal_energy()) / energy - 1.0).abs() < 1e-5);
+		assert!(mix.get_temperature() < 600.0 && mix.get_temperature() > 590.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_cache_audit() {
+		initialize_gases();
+		set_cache_audit(true);
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 10.0);
+		assert_eq!(mix.heat_capacity(), 200.0);
+		assert!(mix.verify_cache());
+		mix.merge(&mix.clone());
+		assert_eq!(mix.heat_capacity(), 400.0);
+		assert!(mix.verify_cache());
+		set_cache_audit(false);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_approx_eq() {
+		initialize_gases();
+		let mut a = Mixture::new();
+		a.set_moles(0, 10.0);
+		a.set_temperature(300.0);
+		let mut b = Mixture::new();
+		b.set_moles(0, 10.0005);
+		b.set_moles(1, 0.0005);
+		b.set_temperature(300.05);
+		assert!(a.approx_eq(&b, 0.001, 0.1));
+		assert!(b.approx_eq(&a, 0.001, 0.1));
+		// a gas only one has counts as its whole amount
+		b.set_moles(2, 1.0);
+		assert!(!a.approx_eq(&b, 0.001, 0.1));
+		b.set_moles(2, 0.0);
+		assert!(!a.approx_eq(&b, 0.001, 0.01));
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_scrub_into() {
+		initialize_gases();
+		let mut source = Mixture::new();
+		source.set_moles(0, 50.0);
+		source.set_moles(1, 50.0);
+		source.set_temperature(300.0);
+		let mut into = Mixture::new();
+		into.set_moles(1, 10.0);
+		into.set_temperature(100.0);
+		source.scrub_into(&[0, 2], &mut into, 0.5);
+		// only the listed gas moves, and gases the source doesn't have are skipped
+		assert_eq!(source.get_moles(0), 25.0);
+		assert_eq!(source.get_moles(1), 50.0);
+		assert_eq!(source.get_temperature(), 300.0);
+		assert_eq!(into.get_moles(0), 25.0);
+		assert_eq!(into.get_moles(2), 0.0);
+		// all gases have the same specific heat: (10 * 100 + 25 * 300) / 35 = ~242.857
+		assert!(
+			(into.get_temperature() - 242.857).abs() < 0.01,
+			"{} should be near 242.857",
+			into.get_temperature()
+		);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_equalize_pressure() {
+		initialize_gases();
+		let mut high = Mixture::from_vol(1000.0);
+		high.set_moles(0, 60.0);
+		high.set_moles(1, 40.0);
+		high.set_temperature(500.0);
+		let mut low = Mixture::from_vol(3000.0);
+		low.set_moles(1, 20.0);
+		low.set_temperature(200.0);
+		let moles_before = high.total_moles() + low.total_moles();
+		let energy_before = high.thermal_energy() + low.thermal_energy();
+		low.equalize_pressure_with(&mut high);
+		assert!(
+			(high.return_pressure() - low.return_pressure()).abs() < 0.01,
+			"{} should be near {}",
+			high.return_pressure(),
+			low.return_pressure()
+		);
+		assert!((high.total_moles() + low.total_moles() - moles_before).abs() < 0.001);
+		assert!(
+			((high.thermal_energy() + low.thermal_energy()) / energy_before - 1.0).abs() < 1e-4
+		);
+		// the high pressure side only loses gas, keeping its makeup and temperature
+		assert_eq!(high.get_temperature(), 500.0);
+		assert!((high.get_moles(0) / high.get_moles(1) - 1.5).abs() < 1e-4);
+		// equal pressures are left alone
+		let (high_before, low_before) = (high.clone(), low.clone());
+		high.equalize_pressure_with(&mut low);
+		assert!(high == high_before && low == low_before);
+		destroy_gas_statics();
+	}
+
+	#[test]
+	fn test_set_moles_bulk() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 5.0);
+		mix.set_moles(1, 5.0);
+		mix.set_moles_bulk(&[(0, f32::NAN), (1, -3.0), (2, f32::INFINITY), (3, 2.0)]);
+		// bad amounts leave the gas as it was, even where it's already in the mix
+		assert_eq!(mix.get_moles(0), 5.0);
+		assert_eq!(mix.get_moles(1), 5.0);
+		assert_eq!(mix.get_moles(2), 0.0);
+		assert_eq!(mix.get_moles(3), 2.0);
+		assert_eq!(mix.total_moles(), 12.0);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_normalize() {
+		initialize_gases();
+		let mut a = Mixture::new();
+		a.set_moles(0, 20.000_2);
+		a.set_moles(1, 0.000_05);
+		a.set_temperature(293.139);
+		let mut b = Mixture::new();
+		b.set_moles(0, 19.999_9);
+		b.set_temperature(293.141);
+		a.normalize(0.001);
+		b.normalize(0.001);
+		// traces go and the temperature snaps, but the moles themselves aren't rounded
+		assert_eq!(a.get_moles(1), 0.0);
+		assert_eq!(a.get_moles(0), 20.000_2);
+		assert_eq!(a.get_temperature(), b.get_temperature());
+		assert_eq!(a.content_hash(), b.content_hash());
+		assert!(!a.needs_normalizing(sim_floors()));
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_gas_vector_growth() {
+		initialize_gases();
+		for id in ["a", "b", "c", "d", "e", "f", "g"] {
+			register_gas_manually(id, 20.0);
+		}
+		// 10 gases, so past the 8 that fit inline
+		let mut mix = Mixture::new();
+		let mut reallocations = 0;
+		for idx in 0..total_num_gases() {
+			let capacity = mix.moles.capacity();
+			mix.set_moles(idx, 10.0);
+			if mix.moles.capacity() != capacity {
+				reallocations += 1;
+			}
+		}
+		// only moving off the inline storage, straight to 16
+		assert_eq!(reallocations, 1);
+		assert_eq!(mix.moles.capacity(), 16);
+		// registering another gas mid-round fits into the space that's already there
+		register_gas_manually("h", 20.0);
+		mix.set_moles(total_num_gases() - 1, 10.0);
+		assert_eq!(mix.moles.capacity(), 16);
+		destroy_gas_statics();
+		// now the case headroom is for: mixes that are exactly full when a gas gets registered mid-round.
+		// kept in this test since the headroom is global and would change the capacities asserted above
+		let without_headroom = bench_mid_round_registration(0);
+		let with_headroom = bench_mid_round_registration(8);
+		println!(
+			"setting a gas registered mid-round on {} mixes: {:?} without headroom, {:?} with 8 slots",
+			BENCH_MIXES, without_headroom, with_headroom
+		);
+	}
+	const BENCH_MIXES: usize = 10_000;
+	// Fills up mixes with 16 gases, registers a 17th and times setting it on all of them.
+	fn bench_mid_round_registration(headroom: usize) -> std::time::Duration {
+		set_gas_vector_headroom(headroom);
+		initialize_gases();
+		for id in [
+			"a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m",
+		] {
+			register_gas_manually(id, 20.0);
+		}
+		let mut mixes: Vec<Mixture> = (0..BENCH_MIXES)
+			.map(|_| {
+				let mut mix = Mixture::new();
+				for idx in 0..total_num_gases() {
+					mix.set_moles(idx, 10.0);
+				}
+				mix
+			})
+			.collect();
+		register_gas_manually("n", 20.0);
+		let new_gas = total_num_gases() - 1;
+		let mut reallocations = 0;
+		let start = std::time::Instant::now();
+		for mix in mixes.iter_mut() {
+			let capacity = mix.moles.capacity();
+			mix.set_moles(new_gas, 10.0);
+			if mix.moles.capacity() != capacity {
+				reallocations += 1;
+			}
+		}
+		let elapsed = start.elapsed();
+		if headroom == 0 {
+			// 16 gases fill a capacity of 16 exactly, so every single mix has to move
+			assert_eq!(reallocations, BENCH_MIXES);
+		} else {
+			assert_eq!(reallocations, 0);
+		}
+		set_gas_vector_headroom(0);
+		destroy_gas_statics();
+		elapsed
+	}
+	#[test]
+	fn test_react_limited() {
+		initialize_gases();
+		let mut mix = Mixture::new();
+		mix.set_moles(0, 10.0);
+		mix.set_moles(1, 1.0);
+		mix.set_temperature(293.15);
+		// o2 + n2 -> n2o, wanting 5 moles of n2 when only 1 is there
+		let extent = mix.react_limited(&[(0, -2.0), (1, -5.0), (2, 4.0)], sim_floors());
+		assert!(
+			(extent - 0.2).abs() < 1e-6,
+			"extent should be 0.2, is {extent}"
+		);
+		assert!(mix.moles.iter().all(|&amt| amt >= 0.0));
+		assert_eq!(mix.get_moles(1), 0.0);
+		assert!((mix.get_moles(0) - 9.6).abs() < 1e-5);
+		assert!((mix.get_moles(2) - 0.8).abs() < 1e-5);
+		// a gas listed more than once is limited by its net change
+		let extent = mix.react_limited(&[(0, -20.0), (0, 8.0), (2, -0.4), (1, 1.0)], sim_floors());
+		assert!(
+			(extent - 0.8).abs() < 1e-5,
+			"extent should be 0.8, is {extent}"
+		);
+		assert!(mix.moles.iter().all(|&amt| amt >= 0.0));
+		assert!(mix.get_moles(0) < 1e-4);
+		assert!((mix.get_moles(2) - 0.48).abs() < 1e-5);
+		assert!((mix.get_moles(1) - 0.8).abs() < 1e-5);
+		// nothing left to use means nothing happens
+		mix.set_moles(0, 0.0);
+		assert_eq!(mix.react_limited(&[(0, -1.0), (1, 1.0)], sim_floors()), 0.0);
+		assert!((mix.get_moles(1) - 0.8).abs() < 1e-5);
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_merge_checked() {
+		initialize_gases();
+		let mut tank = Mixture::from_vol(70.0);
+		tank.set_moles(0, 10.0);
+		tank.set_temperature(293.15);
+		let mut giver = Mixture::new();
+		giver.set_moles(1, 10.0);
+		giver.set_temperature(293.15);
+		let merged_pressure = 20.0 * R_IDEAL_GAS_EQUATION * 293.15 / 70.0;
+		let pressure = match tank.merge_checked(&giver, merged_pressure - 1.0) {
+			Err(MergeRefusal::Overpressure(pressure)) => pressure,
+			_ => panic!("merge should have been refused for overpressure"),
+		};
+		assert!((pressure - merged_pressure).abs() < 0.1);
+		// too much pressure means nothing happens
+		assert_eq!(tank.get_moles(1), 0.0);
+		assert_eq!(tank.get_moles(0), 10.0);
+		assert!(tank.merge_checked(&giver, merged_pressure + 1.0).is_ok());
+		assert_eq!(tank.get_moles(1), 10.0);
+		assert!((tank.return_pressure() - merged_pressure).abs() < 0.1);
+		// an immutable mix refuses outright, whatever the pressure
+		tank.mark_immutable();
+		assert!(matches!(
+			tank.merge_checked(&giver, f32::INFINITY),
+			Err(MergeRefusal::Immutable)
+		));
+		destroy_gas_statics();
+	}
+	#[test]
+	fn test_presence_mask() {
+		initialize_gases();
+		let o2 = BitSet::from_indices([0]);
+		let o2_n2o = BitSet::from_indices([0, 2]);
+		let mut mix = Mixture::new();
+		assert!(!mix.presence_mask().is_superset(&o2));
+		mix.set_moles(0, 10.0);
+		mix.set_moles(2, GAS_MIN_MOLES / 2.0);
+		assert!(mix.presence_mask().is_superset(&o2));
+		assert!(!mix.presence_mask().is_superset(&o2_n2o));
+		mix.adjust_moles(2, 1.0);
+		assert!(mix.presence_mask().is_superset(&o2_n2o));
+		let copy = mix.clone();
+		mix.clear();
+		assert!(!mix.presence_mask().is_superset(&o2));
+		assert!(copy.presence_mask().is_superset(&o2_n2o));
+		// gases past the first word still count
+		assert!(!BitSet::from_indices([3]).is_superset(&BitSet::from_indices([70])));
+		assert!(BitSet::from_indices([3, 70]).is_superset(&BitSet::from_indices([70])));
+		destroy_gas_statics();
+	}
+	#[test]
+	#[cfg(feature = "fusion_hook")]
+	fn test_fusion_scarce_tritium() {
+		initialize_gases();
+		for gas in [GAS_PLASMA, GAS_CO2, GAS_TRITIUM, GAS_H2O, GAS_BZ] {
+			register_gas_manually(gas, 20.0);
+		}
+		let fusion = crate::reaction::hooks::func_from_id("fusion").unwrap();
+		let plasma = gas_idx_from_string(GAS_PLASMA).unwrap();
+		let co2 = gas_idx_from_string(GAS_CO2).unwrap();
+		let tritium = gas_idx_from_string(GAS_TRITIUM).unwrap();
+		let bz = gas_idx_from_string(GAS_BZ).unwrap();
+		let o2 = gas_idx_from_string(GAS_O2).unwrap();
+		let fuse = |trit: f32| {
+			let mut mix = Mixture::new();
+			mix.set_moles(plasma, 400.0);
+			mix.set_moles(co2, 300.0);
+			mix.set_moles(tritium, trit);
+			mix.set_temperature(100_000.0);
+			fusion(&mut mix, sim_floors(), &mut Vec::new()).unwrap();
+			mix
+		};
+		let full = fuse(10.0);
+		// fusion uses a mole of tritium, so this only gets halfway
+		let scarce = fuse(0.5);
+		assert!(scarce.enumerate().all(|(_, amt)| amt >= 0.0));
+		assert_eq!(scarce.get_moles(tritium), 0.0);
+		let full_co2 = full.get_moles(co2) - 300.0;
+		assert!(full_co2.abs() > 1.0);
+		assert!(
+			(scarce.get_moles(co2) - 300.0 - full_co2 * 0.5).abs() < 0.01,
+			"co2 should change by {}, changed by {}",
+			full_co2 * 0.5,
+			scarce.get_moles(co2) - 300.0
+		);
+		for product in [bz, o2] {
+			assert!(full.get_moles(product) > 0.0);
+			assert!((scarce.get_moles(product) - full.get_moles(product) * 0.5).abs() < 1e-4);
+		}
+		destroy_gas_statics();
+	}
 }
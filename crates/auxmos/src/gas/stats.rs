@@ -0,0 +1,127 @@
+// Server-wide totals of how much of each gas was made and used up each tick, for balance graphs.
+// Reactions and planetary atmos add to these as they go; `end_tick` moves them to the last tick's totals.
+// Nothing is recorded until something reads them, see `enable`.
+
+use auxtools::shutdown;
+
+use super::{total_num_gases, GasIDX, Mixture};
+
+use parking_lot::{const_mutex, const_rwlock, Mutex, RwLock};
+
+use std::sync::{
+	atomic::{AtomicBool, Ordering::Relaxed},
+	Arc,
+};
+
+// (produced, consumed) per gas.
+type Totals = Vec<(f32, f32)>;
+
+// Whether anything has asked for the stats yet.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// Every thread's totals for the tick in progress, added up by end_tick.
+static THREAD_TOTALS: Mutex<Vec<Arc<Mutex<Totals>>>> = const_mutex(Vec::new());
+
+// (produced, consumed) per gas, for the last finished tick.
+static LAST_TICK: RwLock<Totals> = const_rwlock(Vec::new());
+
+thread_local! {
+	// This thread's totals for the tick in progress. Only end_tick ever locks it from another thread, so locking it is all but free.
+	static TOTALS: Arc<Mutex<Totals>> = {
+		let totals = Arc::new(Mutex::new(Vec::with_capacity(total_num_gases())));
+		THREAD_TOTALS.lock().push(Arc::clone(&totals));
+		totals
+	};
+}
+
+/// Starts recording stats, if they weren't already. Until this is called, recording them does nothing.
+pub fn enable() {
+	ENABLED.store(true, Relaxed);
+}
+
+/// Whether stats are being recorded; there's no need to work out what changed if this is false.
+#[must_use]
+pub fn enabled() -> bool {
+	ENABLED.load(Relaxed)
+}
+
+// Runs the closure on this thread's totals for the tick in progress.
+fn with_totals(f: impl FnOnce(&mut Totals)) {
+	TOTALS.with(|totals| f(&mut totals.lock()));
+}
+
+// Adds a change in the given gas to the totals: made if positive, used up if negative.
+fn add_change(totals: &mut Totals, idx: GasIDX, delta: f32) {
+	if !delta.is_normal() {
+		return;
+	}
+	if totals.len() <= idx {
+		totals.resize(idx + 1, (0.0, 0.0));
+	}
+	if delta > 0.0 {
+		totals[idx].0 += delta;
+	} else {
+		totals[idx].1 -= delta;
+	}
+}
+
+/// Adds a change in the given gas to this tick's totals: made if positive, used up if negative.
+pub fn record_change(idx: GasIDX, delta: f32) {
+	if enabled() {
+		with_totals(|totals| add_change(totals, idx, delta));
+	}
+}
+
+/// Adds every change in the mix's gases since `before`, from `Mixture::moles_copy`, to this tick's totals.
+pub fn record_difference(before: &[f32], after: &Mixture) {
+	if !enabled() {
+		return;
+	}
+	with_totals(|totals| {
+		let mut after_len = 0;
+		for (idx, amt) in after.enumerate() {
+			add_change(totals, idx, amt - before.get(idx).copied().unwrap_or(0.0));
+			after_len = idx + 1;
+		}
+		for (idx, &amt) in before.iter().enumerate().skip(after_len) {
+			add_change(totals, idx, -amt);
+		}
+	});
+}
+
+/// Finishes the tick: every thread's totals are added up into the last tick's, and the next tick starts from zero.
+pub fn end_tick() {
+	if !enabled() {
+		return;
+	}
+	let mut last_tick = vec![(0.0, 0.0); total_num_gases()];
+	for totals in THREAD_TOTALS.lock().iter() {
+		let mut totals = totals.lock();
+		if last_tick.len() < totals.len() {
+			last_tick.resize(totals.len(), (0.0, 0.0));
+		}
+		for ((produced, consumed), (thread_produced, thread_consumed)) in
+			last_tick.iter_mut().zip(totals.iter_mut())
+		{
+			*produced += std::mem::take(thread_produced);
+			*consumed += std::mem::take(thread_consumed);
+		}
+	}
+	*LAST_TICK.write() = last_tick;
+}
+
+/// The (produced, consumed) moles of each gas, indexed by gas, during the last finished tick.
+#[must_use]
+pub fn last_tick_stats() -> Vec<(f32, f32)> {
+	LAST_TICK.read().clone()
+}
+
+#[shutdown]
+fn _shutdown_gas_stats() {
+	ENABLED.store(false, Relaxed);
+	// threads keep their totals around, so they're emptied rather than dropped
+	for totals in THREAD_TOTALS.lock().iter() {
+		totals.lock().clear();
+	}
+	LAST_TICK.write().clear();
+}
@@ -1,11 +1,13 @@
 use auxcallback::byond_callback_sender;
 use auxtools::*;
 
+use bitflags::bitflags;
+
 use fxhash::FxBuildHasher;
 
 use parking_lot::{const_rwlock, RwLock};
 
-use crate::reaction::{Reaction, ReactionPriority};
+use crate::reaction::{Reaction, ReactionOrder};
 
 use super::GasIDX;
 
@@ -19,7 +21,7 @@ use std::{
 
 static TOTAL_NUM_GASES: AtomicUsize = AtomicUsize::new(0);
 
-static REACTION_INFO: RwLock<Option<BTreeMap<ReactionPriority, Reaction>>> = const_rwlock(None);
+static REACTION_INFO: RwLock<Option<BTreeMap<ReactionOrder, Reaction>>> = const_rwlock(None);
 
 /// The temperature at which this gas can oxidize and how much fuel it can oxidize when it can.
 #[derive(Clone, Copy)]
@@ -99,6 +101,32 @@ impl GasRef {
 	}
 }
 
+bitflags! {
+	/// What a gas can be used for, so reactions and breathing can check for these instead of specific gases.
+	/// Byond gets these as plain numbers: 1 oxidizer, 2 fuel, 4 fusion fuel, 8 breathable.
+	#[derive(Default)]
+	pub struct GasFlags: u32 {
+		const OXIDIZER = 0b1;
+		const FUEL = 0b10;
+		const FUSION_FUEL = 0b100;
+		const BREATHABLE = 0b1000;
+	}
+}
+
+impl GasFlags {
+	/// The flags that follow from a gas's fire info and fusion power.
+	#[must_use]
+	pub fn inferred(fire_info: &FireInfo, fusion_power: f32) -> Self {
+		let mut flags = match fire_info {
+			FireInfo::Oxidation(_) => Self::OXIDIZER,
+			FireInfo::Fuel(_) => Self::FUEL,
+			FireInfo::None => Self::empty(),
+		};
+		flags.set(Self::FUSION_FUEL, fusion_power != 0.0);
+		flags
+	}
+}
+
 #[derive(Clone)]
 pub enum FireProductInfo {
 	Generic(Vec<(GasRef, f32)>),
@@ -122,9 +150,21 @@ pub struct GasType {
 	pub name: Box<str>,
 	/// Byond: `flags`, a number (bitflags).
 	pub flags: u32,
+	/// What the gas can be used for. Not to be confused with `flags`, which are only used by byond.
+	/// Byond: inferred from fire info and `fusion_power`, plus `breathable`, a number, for BREATHABLE.
+	pub gas_flags: GasFlags,
 	/// The specific heat of the gas. Duplicated in the GAS_SPECIFIC_HEATS vector for speed.
 	/// Byond: `specific_heat`, a number.
 	pub specific_heat: f32,
+	/// Grams per mole. If None, the gas's mass is unknown and counted as nothing.
+	/// Byond: `molar_mass`, a number; if it's not there, known gases use their real molar mass.
+	pub molar_mass: Option<f32>,
+	/// The temperature below which the gas would condense, in kelvins. If None, the gas never condenses.
+	/// Byond: `condensation_point`, a number.
+	pub condensation_point: Option<f32>,
+	/// How the pressure the gas saturates at changes with temperature. If None, the gas never saturates.
+	/// Byond: `saturation_a`, `saturation_b` and `saturation_c`, numbers; if they're not there, known gases use their real curve.
+	pub saturation_curve: Option<SaturationCurve>,
 	/// Gas's fusion power. Used in fusion hooking, so this can be removed and ignored if you don't have fusion.
 	/// Byond: `fusion_power`, a number.
 	pub fusion_power: f32,
@@ -145,14 +185,71 @@ pub struct GasType {
 	pub fire_products: Option<FireProductInfo>,
 }
 
+/// An Antoine equation for the pressure a gas saturates at: `log10(P) = a - b / (c + T)`, with `P` in kilopascals and `T` in kelvins.
+#[derive(Clone, Copy)]
+pub struct SaturationCurve {
+	pub a: f32,
+	pub b: f32,
+	pub c: f32,
+}
+
+impl SaturationCurve {
+	/// The saturation vapor pressure at the given temperature, in kilopascals.
+	/// Zero below where the curve makes sense, where `c + T` isn't positive.
+	#[must_use]
+	pub fn pressure_at(&self, temperature: f32) -> f32 {
+		let denominator = self.c + temperature;
+		if denominator <= 0.0 {
+			0.0
+		} else {
+			10.0_f32.powf(self.a - self.b / denominator)
+		}
+	}
+}
+
+// Real saturation curves for the gases that have them, used if the gas datum doesn't give one.
+fn known_saturation_curve(id: &str) -> Option<SaturationCurve> {
+	use super::constants::GAS_H2O;
+	match id {
+		GAS_H2O => Some(SaturationCurve {
+			a: 7.196_21,
+			b: 1730.63,
+			c: -39.724,
+		}),
+		_ => None,
+	}
+}
+
+// Real molar masses, in grams per mole, for the gases that have them, used if the gas datum doesn't give one.
+fn known_molar_mass(id: &str) -> Option<f32> {
+	use super::constants::{
+		GAS_BZ, GAS_CO2, GAS_H2O, GAS_METHANE, GAS_METHYL_BROMIDE, GAS_N2, GAS_NITROUS, GAS_NITRYL,
+		GAS_O2, GAS_TRITIUM,
+	};
+	match id {
+		GAS_O2 => Some(32.0),
+		GAS_N2 => Some(28.0),
+		GAS_CO2 => Some(44.0),
+		GAS_H2O => Some(18.0),
+		GAS_NITROUS => Some(44.0),
+		GAS_NITRYL => Some(46.0),
+		GAS_TRITIUM => Some(6.0),
+		GAS_BZ => Some(337.4),
+		GAS_METHANE => Some(16.0),
+		GAS_METHYL_BROMIDE => Some(94.9),
+		_ => None,
+	}
+}
+
 impl GasType {
 	// This absolute monster is what you want to override to add or remove certain gas properties, based on what a gas datum has.
 	fn new(gas: &Value, idx: GasIDX) -> Result<Self, Runtime> {
-		Ok(Self {
+		let mut gas_type = Self {
 			idx,
 			id: gas.get_string(byond_string!("id"))?.into_boxed_str(),
 			name: gas.get_string(byond_string!("name"))?.into_boxed_str(),
 			flags: gas.get_number(byond_string!("flags")).unwrap_or_default() as u32,
+			gas_flags: GasFlags::empty(),
 			specific_heat: gas
 				.get_number(byond_string!("specific_heat"))
 				.map_err(|_| {
@@ -163,6 +260,27 @@ impl GasType {
 						std::column!()
 					)
 				})?,
+			molar_mass: gas
+				.get_number(byond_string!("molar_mass"))
+				.ok()
+				.filter(|&mass| mass > 0.0)
+				.or_else(|| {
+					known_molar_mass(&gas.get_string(byond_string!("id")).unwrap_or_default())
+				}),
+			condensation_point: gas
+				.get_number(byond_string!("condensation_point"))
+				.ok()
+				.filter(|&temp| temp > 0.0),
+			saturation_curve: match (
+				gas.get_number(byond_string!("saturation_a")),
+				gas.get_number(byond_string!("saturation_b")),
+				gas.get_number(byond_string!("saturation_c")),
+			) {
+				(Ok(a), Ok(b), Ok(c)) => Some(SaturationCurve { a, b, c }),
+				_ => {
+					known_saturation_curve(&gas.get_string(byond_string!("id")).unwrap_or_default())
+				}
+			},
 			fusion_power: gas
 				.get_number(byond_string!("fusion_power"))
 				.unwrap_or_default(),
@@ -214,7 +332,15 @@ impl GasType {
 			fire_radiation_released: gas
 				.get_number(byond_string!("fire_radiation_released"))
 				.unwrap_or_default(),
-		})
+		};
+		gas_type.gas_flags = GasFlags::inferred(&gas_type.fire_info, gas_type.fusion_power);
+		gas_type.gas_flags.set(
+			GasFlags::BREATHABLE,
+			gas.get_number(byond_string!("breathable"))
+				.unwrap_or_default()
+				!= 0.0,
+		);
+		Ok(gas_type)
 	}
 }
 
@@ -251,22 +377,27 @@ fn _destroy_gas_info_structs() {
 	});
 }
 
-#[hook("/proc/_auxtools_register_gas")]
-fn _hook_register_gas(gas: Value) {
-	let gas_id = gas.get_string(byond_string!("id"))?;
+/// Adds a gas to the registry, or updates it in place if a gas with that ID already exists.
+/// The closure is given the gas's index and, if it's being updated, the gas's current info.
+/// Returns the gas's index.
+fn register_gas(
+	gas_id: String,
+	make_gas: impl FnOnce(GasIDX, Option<&GasType>) -> Result<GasType, Runtime>,
+) -> Result<GasIDX, Runtime> {
 	match {
 		unsafe { GAS_INFO_BY_STRING.as_ref() }
 			.unwrap()
 			.get_mut(&gas_id as &str)
 	} {
 		Some(mut old_gas) => {
-			let gas_cache = GasType::new(gas, old_gas.idx)?;
+			let gas_cache = make_gas(old_gas.idx, Some(&*old_gas))?;
 			*old_gas = gas_cache.clone();
 			GAS_SPECIFIC_HEATS.write().as_mut().unwrap()[old_gas.idx] = gas_cache.specific_heat;
 			GAS_INFO_BY_IDX.write().as_mut().unwrap()[old_gas.idx] = gas_cache;
+			Ok(old_gas.idx)
 		}
 		None => {
-			let gas_cache = GasType::new(gas, TOTAL_NUM_GASES.load(Ordering::Acquire))?;
+			let gas_cache = make_gas(TOTAL_NUM_GASES.load(Ordering::Acquire), None)?;
 			let cached_id = gas_id.clone();
 			let cached_idx = gas_cache.idx;
 			unsafe { GAS_INFO_BY_STRING.as_ref() }
@@ -283,11 +414,97 @@ fn _hook_register_gas(gas: Value) {
 				map.insert(cached_idx, cached_id.into_boxed_str())
 			});
 			TOTAL_NUM_GASES.fetch_add(1, Ordering::Release); // this is the only thing that stores it other than shutdown
+			Ok(cached_idx)
 		}
 	}
+}
+
+#[hook("/proc/_auxtools_register_gas")]
+fn _hook_register_gas(gas: Value) {
+	let gas_id = gas.get_string(byond_string!("id"))?;
+	register_gas(gas_id, |idx, _| GasType::new(gas, idx))?;
 	Ok(Value::null())
 }
 
+/// Args: (id, specific_heat, flags, name, gas_flags, molar_mass). Registers a gas without needing a gas datum, e.g. for gases added by mods at runtime.
+/// `gas_flags` are the ones given by auxmos_gas_flags, but only BREATHABLE (8) can be set this way; the others always follow
+/// the gas's fire info and fusion power, so a new gas registered this way is never an oxidizer, fuel or fusion fuel.
+/// If the gas already exists, its specific heat, flags and name are updated and everything else is kept.
+/// Its gas flags and molar mass, in grams per mole, are also updated if given. A new gas without a molar mass has none.
+/// Reactions only see new gases after reactions are updated.
+/// Returns: the index of the gas.
+#[hook("/proc/auxmos_register_gas")]
+fn _hook_auxmos_register_gas(
+	id: Value,
+	specific_heat: Value,
+	flags: Value,
+	name: Value,
+	gas_flags: Value,
+	molar_mass: Value,
+) {
+	let gas_id = id.as_string()?;
+	let specific_heat = specific_heat.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if !specific_heat.is_finite() || specific_heat < 0.0 {
+		return Err(runtime!(
+			"Invalid specific heat for gas {}: {}",
+			gas_id,
+			specific_heat
+		));
+	}
+	let flags = flags.as_number().unwrap_or_default() as u32;
+	let name = name.as_string().unwrap_or_else(|_| gas_id.clone());
+	let gas_flags = gas_flags
+		.as_number()
+		.ok()
+		.map(|bits| GasFlags::from_bits_truncate(bits as u32));
+	let molar_mass = molar_mass.as_number().ok().filter(|&mass| mass > 0.0);
+	// only BREATHABLE is taken from the given flags, the rest are the gas's own
+	let with_breathable =
+		|inferred: GasFlags, given: GasFlags| inferred | (given & GasFlags::BREATHABLE);
+	let idx = register_gas(gas_id.clone(), |idx, old_gas| {
+		Ok(match old_gas {
+			Some(old_gas) => GasType {
+				name: name.into_boxed_str(),
+				flags,
+				gas_flags: gas_flags.map_or(old_gas.gas_flags, |given| {
+					with_breathable(
+						GasFlags::inferred(&old_gas.fire_info, old_gas.fusion_power),
+						given,
+					)
+				}),
+				specific_heat,
+				molar_mass: molar_mass.or(old_gas.molar_mass),
+				..old_gas.clone()
+			},
+			None => GasType {
+				idx,
+				id: gas_id.into_boxed_str(),
+				name: name.into_boxed_str(),
+				flags,
+				gas_flags: with_breathable(GasFlags::empty(), gas_flags.unwrap_or_default()),
+				specific_heat,
+				molar_mass,
+				condensation_point: None,
+				saturation_curve: None,
+				fusion_power: 0.0,
+				moles_visible: None,
+				enthalpy: 0.0,
+				fire_radiation_released: 0.0,
+				fire_info: FireInfo::None,
+				fire_products: None,
+			},
+		})
+	})?;
+	Ok(Value::from(idx as f32))
+}
+
 #[hook("/proc/auxtools_atmos_init")]
 fn _hook_init() {
 	let data = Value::globals()
@@ -304,29 +521,21 @@ fn _hook_init() {
 	Ok(Value::from(true))
 }
 
-fn get_reaction_info() -> BTreeMap<ReactionPriority, Reaction> {
+fn get_reaction_info() -> BTreeMap<ReactionOrder, Reaction> {
 	let gas_reactions = Value::globals()
 		.get(byond_string!("SSair"))
 		.unwrap()
 		.get_list(byond_string!("gas_reactions"))
 		.unwrap();
-	let mut reaction_cache: BTreeMap<ReactionPriority, Reaction> = Default::default();
+	let mut reaction_cache: BTreeMap<ReactionOrder, Reaction> = Default::default();
 	let sender = byond_callback_sender();
 	for i in 1..=gas_reactions.len() {
 		match Reaction::from_byond_reaction(&gas_reactions.get(i).unwrap()) {
 			Ok(reaction) => {
-				if let std::collections::btree_map::Entry::Vacant(e) =
-					reaction_cache.entry(reaction.get_priority())
-				{
-					e.insert(reaction);
-				} else {
-					drop(sender.try_send(Box::new(move || {
-						Err(runtime!(format!(
-							"Duplicate reaction priority {}, this reaction will be ignored!",
-							reaction.get_priority().0
-						)))
-					})));
-				}
+				reaction_cache.insert(
+					(reaction.get_priority(), std::cmp::Reverse(i as usize)),
+					reaction,
+				);
 			}
 			//maybe awful error handling
 			Err(runtime) => {
@@ -348,7 +557,7 @@ fn _update_reactions() {
 /// If reactions aren't loaded yet.
 pub fn with_reactions<T, F>(mut f: F) -> T
 where
-	F: FnMut(&BTreeMap<ReactionPriority, Reaction>) -> T,
+	F: FnMut(&BTreeMap<ReactionOrder, Reaction>) -> T,
 {
 	f(REACTION_INFO
 		.read()
@@ -397,6 +606,59 @@ pub fn gas_visibility(idx: usize) -> Option<f32> {
 		.moles_visible
 }
 
+/// Gets the flags of the gas with the given index; none if there's no such gas.
+/// # Panics
+/// If gas info isn't loaded yet.
+#[must_use]
+pub fn gas_flags(idx: GasIDX) -> GasFlags {
+	with_gas_info(|gas_info| {
+		gas_info
+			.get(idx)
+			.map_or_else(GasFlags::empty, |gas| gas.gas_flags)
+	})
+}
+
+/// Gets the indices of every gas that has all of the given flags, in index order.
+/// # Panics
+/// If gas info isn't loaded yet.
+#[must_use]
+pub fn gases_with_flag(flag: GasFlags) -> Vec<GasIDX> {
+	with_gas_info(|gas_info| {
+		gas_info
+			.iter()
+			.filter(|gas| gas.gas_flags.contains(flag))
+			.map(|gas| gas.idx)
+			.collect()
+	})
+}
+
+/// Gets the indices of every gas with a specific heat from `min` to `max`, inclusive, in index order.
+/// # Panics
+/// If gas info isn't loaded yet.
+#[must_use]
+pub fn gases_in_specific_heat_range(min: f32, max: f32) -> Vec<GasIDX> {
+	with_gas_info(|gas_info| {
+		gas_info
+			.iter()
+			.filter(|gas| (min..=max).contains(&gas.specific_heat))
+			.map(|gas| gas.idx)
+			.collect()
+	})
+}
+
+/// Gets the moles at which each gas becomes visible, for every gas that can be.
+/// # Panics
+/// If gas info isn't loaded yet.
+#[must_use]
+pub fn visibility_thresholds() -> Vec<(GasIDX, f32)> {
+	with_gas_info(|gas_info| {
+		gas_info
+			.iter()
+			.filter_map(|g| Some((g.idx, g.moles_visible?)))
+			.collect()
+	})
+}
+
 /// Gets a copy of all the gas visibilities.
 /// # Panics
 /// If gas info isn't loaded yet.
@@ -493,6 +755,92 @@ pub fn gas_idx_to_id(idx: GasIDX) -> DMResult {
 	})
 }
 
+/// Returns: a list of the string IDs of every registered gas, in index order.
+#[hook("/proc/auxmos_all_gas_ids")]
+fn _hook_all_gas_ids() {
+	let ids = with_gas_info(|gases| gases.iter().map(|gas| gas.id.clone()).collect::<Vec<_>>());
+	let ids_list = List::new();
+	for id in ids.iter() {
+		ids_list.append(Value::from_string(id)?);
+	}
+	Ok(Value::from(ids_list))
+}
+
+/// Args: (id). Returns: the index auxmos uses for the gas with the given string ID.
+#[hook("/proc/auxmos_gas_id_to_index")]
+fn _hook_gas_id_to_index(id: Value) {
+	Ok(Value::from(gas_idx_from_value(id)? as f32))
+}
+
+/// Args: (index). Returns: the string ID of the gas with the given index.
+#[hook("/proc/auxmos_gas_index_to_id")]
+fn _hook_gas_index_to_id(index: Value) {
+	let idx = index.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if idx < 0.0 || idx as usize >= total_num_gases() || idx.fract() != 0.0 {
+		return Err(runtime!("Invalid gas index: {}", idx));
+	}
+	gas_idx_to_id(idx as usize)
+}
+
+/// Args: (gas_id). Returns: the gas's flags, as a number: 1 oxidizer, 2 fuel, 4 fusion fuel, 8 breathable.
+#[hook("/proc/auxmos_gas_flags")]
+fn _hook_gas_flags(id: Value) {
+	Ok(Value::from(gas_flags(gas_idx_from_value(id)?).bits() as f32))
+}
+
+// Makes a byond list of the string IDs of the given gases.
+fn gas_id_list(indices: &[GasIDX]) -> DMResult {
+	let list = List::new();
+	for &idx in indices {
+		list.append(gas_idx_to_id(idx)?);
+	}
+	Ok(Value::from(list))
+}
+
+/// Args: (flags), as a number: 1 oxidizer, 2 fuel, 4 fusion fuel, 8 breathable.
+/// Returns: a list of the string IDs of every gas that has all of those flags.
+#[hook("/proc/auxmos_gases_with_flag")]
+fn _hook_gases_with_flag(flags: Value) {
+	let flags = flags.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	gas_id_list(&gases_with_flag(GasFlags::from_bits_truncate(flags as u32)))
+}
+
+/// Args: (min, max). Returns: a list of the string IDs of every gas with a specific heat from min to max, inclusive.
+#[hook("/proc/auxmos_gases_in_specific_heat_range")]
+fn _hook_gases_in_specific_heat_range(min: Value, max: Value) {
+	let min = min.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let max = max.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	gas_id_list(&gases_in_specific_heat_range(min, max))
+}
+
 #[cfg(test)]
 pub fn register_gas_manually(gas_id: &'static str, specific_heat: f32) {
 	let gas_cache = GasType {
@@ -500,7 +848,11 @@ pub fn register_gas_manually(gas_id: &'static str, specific_heat: f32) {
 		id: gas_id.into(),
 		name: gas_id.into(),
 		flags: 0,
+		gas_flags: GasFlags::empty(),
 		specific_heat,
+		molar_mass: None,
+		condensation_point: None,
+		saturation_curve: None,
 		fusion_power: 0.0,
 		moles_visible: None,
 		enthalpy: 0.0,
@@ -7,17 +7,21 @@ mod reaction;
 
 mod parser;
 
-use auxtools::{byond_string, hook, inventory, runtime, List, Value};
+use auxtools::{byond_string, hook, inventory, runtime, List, Runtime, Value};
 
 use auxcleanup::{datum_del, DelDatumFunc};
 
 use gas::{
-	amt_gases, constants, gas_idx_from_string, gas_idx_from_value, gas_idx_to_id, tot_gases, types,
-	with_gas_info, with_mix, with_mix_mut, with_mixes, with_mixes_custom, with_mixes_mut, GasArena,
-	Mixture,
+	amt_gases, constants, gas_idx_from_string, gas_idx_from_value, gas_idx_to_id, mix_id,
+	mixture::MergeRefusal, tot_gases, types, with_gas_info, with_mix, with_mix_mut, with_mixes,
+	with_mixes_custom, with_mixes_mut, GasArena, Mixture,
 };
 
-use reaction::react_by_id;
+use reaction::{
+	react_allowed, react_by_id, react_in_context, react_preview, react_until_stable,
+	react_with_effects, react_with_report, reaction_id_from_string, reaction_string_id,
+	ReactionEnv,
+};
 
 use gas::constants::{ReactionReturn, GAS_MIN_MOLES, MINIMUM_MOLES_DELTA_TO_MOVE};
 
@@ -65,18 +69,122 @@ fn _min_heat_cap_hook(arg_min: Value) {
 	})
 }
 
+/// Args: (gas_id, specific_heat). Makes this mixture treat the given gas as having the given specific heat, instead of the usual one.
+#[hook("/datum/gas_mixture/proc/set_heat_capacity_override")]
+fn _set_heat_capacity_override_hook(gas_id: Value, value: Value) {
+	let idx = gas_idx_from_value(gas_id)?;
+	let specific_heat = value.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if !specific_heat.is_finite() || specific_heat < 0.0 {
+		return Err(runtime!(
+			"Invalid specific heat override: {}",
+			specific_heat
+		));
+	}
+	with_mix_mut(src, |mix| {
+		mix.set_heat_capacity_override(idx, specific_heat);
+		Ok(Value::null())
+	})
+}
+
+/// Args: (gas_id). Makes this mixture use the usual specific heat for the given gas again.
+#[hook("/datum/gas_mixture/proc/clear_heat_capacity_override")]
+fn _clear_heat_capacity_override_hook(gas_id: Value) {
+	let idx = gas_idx_from_value(gas_id)?;
+	with_mix_mut(src, |mix| {
+		mix.clear_heat_capacity_override(idx);
+		Ok(Value::null())
+	})
+}
+
 /// Returns: Amount of substance, in moles.
 #[hook("/datum/gas_mixture/proc/total_moles")]
 fn _total_moles_hook() {
 	with_mix(src, |mix| Ok(Value::from(mix.total_moles())))
 }
 
+/// Returns: whether the mixture has effectively no gas in it.
+#[hook("/datum/gas_mixture/proc/is_empty")]
+fn _is_empty_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.is_empty())))
+}
+
 /// Returns: the mix's pressure, in kilopascals.
 #[hook("/datum/gas_mixture/proc/return_pressure")]
 fn _return_pressure_hook() {
 	with_mix(src, |mix| Ok(Value::from(mix.return_pressure())))
 }
 
+/// Args: (volume). Returns: the pressure the mix would be at with the given volume, in kilopascals. The mix isn't changed.
+#[hook("/datum/gas_mixture/proc/pressure_at_volume")]
+fn _pressure_at_volume_hook(volume: Value) {
+	let volume = volume.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if volume.is_nan() || volume <= 0.0 {
+		return Err(runtime!(
+			"Attempted to get the pressure at a non-positive volume: {}",
+			volume
+		));
+	}
+	with_mix(src, |mix| Ok(Value::from(mix.pressure_at_volume(volume))))
+}
+
+/// Returns: the mix's specific entropy, per mole. See `Mixture::specific_entropy` for the equation.
+#[hook("/datum/gas_mixture/proc/specific_entropy")]
+fn _specific_entropy_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.specific_entropy())))
+}
+
+/// Returns: the mix's total mass, in grams. Gases without a known molar mass don't count.
+#[hook("/datum/gas_mixture/proc/total_mass")]
+fn _total_mass_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.mass())))
+}
+
+/// Args: (gas_id). Returns: the mass of that gas in the mix, in grams, or 0 if its molar mass isn't known.
+#[hook("/datum/gas_mixture/proc/gas_mass")]
+fn _gas_mass_hook(gas_id: Value) {
+	with_mix(src, |mix| {
+		Ok(Value::from(mix.gas_mass(gas_idx_from_value(gas_id)?)))
+	})
+}
+
+/// Returns: an associative list of gas IDs to the moles that would condense, for every gas below its condensation point.
+/// Nothing is removed from the mix; what happens to the condensed gas is up to the caller.
+#[hook("/datum/gas_mixture/proc/condensables")]
+fn _condensables_hook() {
+	with_mix(src, |mix| {
+		let condensables = List::new();
+		for (idx, amt) in mix.condensable_gases() {
+			condensables.set(gas_idx_to_id(idx)?, Value::from(amt))?;
+		}
+		Ok(Value::from(condensables))
+	})
+}
+
+/// Args: (gas_id). Returns: how many moles of that gas are over what the mix can hold at its temperature, or 0 if the gas doesn't saturate.
+/// Nothing is removed from the mix; condensing the excess is up to the caller.
+#[hook("/datum/gas_mixture/proc/saturation_excess")]
+fn _saturation_excess_hook(gas_id: Value) {
+	with_mix(src, |mix| {
+		Ok(Value::from(
+			mix.saturation_excess(gas_idx_from_value(gas_id)?),
+		))
+	})
+}
+
 /// Returns: the mix's temperature, in kelvins.
 #[hook("/datum/gas_mixture/proc/return_temperature")]
 fn _return_temperature_hook() {
@@ -95,6 +203,16 @@ fn _thermal_energy_hook() {
 	with_mix(src, |mix| Ok(Value::from(mix.thermal_energy())))
 }
 
+/// Args: (gas_id). Returns: the thermal energy of just that gas in the mix, its partial heat capacity times the mix's temperature.
+#[hook("/datum/gas_mixture/proc/gas_thermal_energy")]
+fn _gas_thermal_energy_hook(gas_id: Value) {
+	with_mix(src, |mix| {
+		Ok(Value::from(
+			mix.thermal_energy_of(gas_idx_from_value(gas_id)?),
+		))
+	})
+}
+
 /// Args: (mixture). Merges the gas from the giver into src, without modifying the giver mix.
 #[hook("/datum/gas_mixture/proc/merge")]
 fn _merge_hook(giver: Value) {
@@ -104,6 +222,57 @@ fn _merge_hook(giver: Value) {
 	})
 }
 
+/// Args: (mixture, max_pressure). Merges the gas from the giver into src like merge, unless src would end up above max_pressure.
+/// Returns: null if it merged; otherwise the pressure src would have been at, with neither mix changed, so the holder can rupture.
+/// Runtimes if src is immutable.
+#[hook("/datum/gas_mixture/proc/merge_checked")]
+fn _merge_checked_hook(giver: Value, max_pressure: Value) {
+	let max_pressure = max_pressure.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mixes_custom(src, giver, |src_mix, giver_mix| {
+		Ok(
+			match src_mix
+				.write()
+				.merge_checked(&giver_mix.read(), max_pressure)
+			{
+				Ok(()) => Value::null(),
+				Err(MergeRefusal::Overpressure(pressure)) => Value::from(pressure),
+				Err(MergeRefusal::Immutable) => {
+					return Err(runtime!(
+						"Attempted to merge into an immutable gas mixture!"
+					))
+				}
+			},
+		)
+	})
+}
+
+/// Args: (mixture, ratio). Merges the given ratio of the giver's gas into src, without modifying the giver mix. Ratio is a number between 0 and 1.
+#[hook("/datum/gas_mixture/proc/merge_fraction")]
+fn _merge_fraction_hook(giver: Value, ratio_arg: Value) {
+	let ratio = ratio_arg.as_number().unwrap_or_default();
+	with_mixes_mut(src, giver, |src_mix, giver_mix| {
+		src_mix.merge_fraction(giver_mix, ratio);
+		Ok(Value::null())
+	})
+}
+
+/// Args: (mixture, t). Moves src's gases and temperature `t` of the way towards those of `mixture`, without modifying `mixture`. `t` is a number between 0 and 1.
+#[hook("/datum/gas_mixture/proc/lerp")]
+fn _lerp_hook(target: Value, t_arg: Value) {
+	let t = t_arg.as_number().unwrap_or_default();
+	with_mixes_mut(src, target, |src_mix, target_mix| {
+		src_mix.lerp_towards(target_mix, t);
+		Ok(Value::null())
+	})
+}
+
 /// Args: (mixture, ratio). Takes the given ratio of gas from src and puts it into the argument mixture. Ratio is a number between 0 and 1.
 #[hook("/datum/gas_mixture/proc/__remove_ratio")]
 fn _remove_ratio_hook(into: Value, ratio_arg: Value) {
@@ -114,6 +283,17 @@ fn _remove_ratio_hook(into: Value, ratio_arg: Value) {
 	})
 }
 
+/// Args: (mixture, ratio). Like __remove_ratio, but the removed gas is put into the argument mixture at the minimum temperature,
+/// while the rest of its heat stays in src, which gets hotter for it. Energy doesn't follow the gas here, on purpose.
+#[hook("/datum/gas_mixture/proc/__remove_ratio_cold")]
+fn _remove_ratio_cold_hook(into: Value, ratio_arg: Value) {
+	let ratio = ratio_arg.as_number().unwrap_or_default();
+	with_mixes_mut(src, into, |src_mix, into_mix| {
+		into_mix.copy_from_mutable(&src_mix.remove_ratio_keep_heat(ratio));
+		Ok(Value::null())
+	})
+}
+
 /// Args: (mixture, amount). Takes the given amount of gas from src and puts it into the argument mixture. Amount is amount of substance in moles.
 #[hook("/datum/gas_mixture/proc/__remove")]
 fn _remove_hook(into: Value, amount_arg: Value) {
@@ -124,11 +304,70 @@ fn _remove_hook(into: Value, amount_arg: Value) {
 	})
 }
 
-/// Arg: (mixture). Makes src into a copy of the argument mixture.
+/// Args: (volume, mixture). Takes a breath of the given volume, in liters, from src: that share of every gas, at src's temperature.
+/// The argument mixture is made into the breath, with the breath's volume.
+/// Returns: the breath mixture.
+#[hook("/datum/gas_mixture/proc/breathe")]
+fn _breathe_hook(volume: Value, breath: Value) {
+	let volume = volume.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if volume.is_nan() || volume <= 0.0 {
+		return Err(runtime!("Invalid breath volume: {}", volume));
+	}
+	with_mixes_mut(src, breath, |src_mix, breath_mix| {
+		let removed = src_mix.breathe(volume);
+		breath_mix.copy_from_mutable(&removed);
+		breath_mix.volume = removed.volume;
+		Ok(())
+	})?;
+	Ok(breath.clone())
+}
+
+/// Args: (co2_moles). Adds the given moles of CO2 to src at its temperature, as breathed out.
+#[hook("/datum/gas_mixture/proc/exhale")]
+fn _exhale_hook(co2_moles: Value) {
+	let co2_moles = co2_moles.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mix_mut(src, |mix| mix.exhale(co2_moles))?;
+	Ok(Value::null())
+}
+
+/// Args: (mixture, amount). Takes the given amount of moles from src, spread over every gas, and makes the argument mixture into exactly what was removed.
+/// If src has less than that, everything is removed; if it's empty, the argument mixture ends up empty.
+#[hook("/datum/gas_mixture/proc/__remove_moles")]
+fn _remove_moles_hook(into: Value, amount_arg: Value) {
+	let amount = amount_arg.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mixes_mut(src, into, |src_mix, into_mix| {
+		into_mix.copy_from_mutable(&src_mix.remove(amount));
+		Ok(Value::null())
+	})
+}
+
+/// Args: (mixture, copy_volume). Makes src into a copy of the argument mixture. Volume is only copied if `copy_volume` is truthy.
 #[hook("/datum/gas_mixture/proc/copy_from")]
-fn _copy_from_hook(giver: Value) {
-	with_mixes_custom(src, giver, |src_mix, giver_mix| {
-		src_mix.write().copy_from_mutable(&giver_mix.read());
+fn _copy_from_hook(giver: Value, copy_volume: Value) {
+	let copy_volume = copy_volume.as_number().map_or(false, |n| n != 0.0);
+	with_mixes_mut(src, giver, |src_mix, giver_mix| {
+		src_mix.copy_from(giver_mix, copy_volume);
 		Ok(Value::null())
 	})
 }
@@ -138,12 +377,21 @@ fn _copy_from_hook(giver: Value) {
 fn _temperature_share_hook() {
 	let arg_num = args.len();
 	match arg_num {
-		2 => with_mixes_mut(src, &args[0], |src_mix, share_mix| {
-			Ok(Value::from(src_mix.temperature_share(
-				share_mix,
-				args[1].as_number().unwrap_or_default(),
-			)))
-		}),
+		2 => {
+			let coefficient = args[1].as_number().map_err(|_| {
+				runtime!(
+					"Attempt to interpret non-number value as number {} {}:{}",
+					std::file!(),
+					std::line!(),
+					std::column!()
+				)
+			})?;
+			with_mixes_mut(src, &args[0], |src_mix, share_mix| {
+				Ok(Value::from(
+					src_mix.temperature_share(share_mix, coefficient),
+				))
+			})
+		}
 		4 => with_mix_mut(src, |mix| {
 			Ok(Value::from(mix.temperature_share_non_gas(
 				args[1].as_number().unwrap_or_default(),
@@ -155,22 +403,257 @@ fn _temperature_share_hook() {
 	}
 }
 
+/// Args: (list). Splits this mixture evenly between every mixture in the list, merging into whatever they already have, and empties this one.
+#[hook("/datum/gas_mixture/proc/split_evenly")]
+fn _split_evenly_hook(outputs: Value) {
+	let outputs_list = outputs.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut ids = Vec::with_capacity(outputs_list.len() as usize + 1);
+	ids.push(mix_id(src)?);
+	for i in 1..=outputs_list.len() {
+		ids.push(mix_id(&outputs_list.get(i)?)?);
+	}
+	GasArena::with_gas_mixtures_many_mut(&ids, |mixes| {
+		let (src_mix, outputs) = mixes.split_first_mut().unwrap();
+		src_mix.split_evenly(outputs)?;
+		Ok(Value::null())
+	})
+}
+
+/// Args: (mixture, coefficient). Exchanges heat, but no gas, between the two mixtures; at a coefficient of 1 both end up at the same temperature.
+#[hook("/datum/gas_mixture/proc/thermal_share")]
+fn _thermal_share_hook(other: Value, coefficient: Value) {
+	let conductivity = coefficient.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mixes_mut(src, other, |src_mix, other_mix| {
+		src_mix.thermal_equalize_with(other_mix, conductivity);
+		Ok(Value::null())
+	})
+}
+
+/// Args: (mixture). Swaps the gases and temperatures of the two mixtures. Volumes aren't swapped,
+/// so pressures change to suit each mixture's volume. Does nothing if either is immutable.
+#[hook("/datum/gas_mixture/proc/swap_with")]
+fn _swap_with_hook(other: Value) {
+	with_mixes_mut(src, other, |src_mix, other_mix| {
+		src_mix.swap_contents(other_mix);
+		Ok(Value::null())
+	})
+}
+
+/// Args: (mixture). Moves gas from whichever mixture is at the higher pressure into the other until they're at the same pressure,
+/// keeping total moles and energy. The gas moved goes at its source's temperature. Does nothing if either is immutable.
+#[hook("/datum/gas_mixture/proc/equalize_pressure")]
+fn _equalize_pressure_hook(other: Value) {
+	with_mixes_mut(src, other, |src_mix, other_mix| {
+		src_mix.equalize_pressure_with(other_mix);
+		Ok(Value::null())
+	})
+}
+
+/// Returns: an associative list with the mixture's "temperature", "volume", "pressure", "heat_capacity", "total_moles", and "gases", an associative list of gas IDs to moles.
+/// Everything is read at once, so the values are all consistent with each other.
+#[hook("/datum/gas_mixture/proc/get_snapshot")]
+fn _get_snapshot_hook() {
+	snapshot_to_list(&with_mix(src, |mix| Ok(mix.snapshot()))?)
+}
+
+/// Args: (n). Makes the mixture keep its last `n` states, recorded every time it shares or reacts, for `get_history`.
+/// 0 stops keeping them. Off by default, since it's slow; meant for debugging a single mixture.
+#[hook("/datum/gas_mixture/proc/enable_history")]
+fn _enable_history_hook(limit: Value) {
+	let limit = limit.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mix_mut(src, |mix| {
+		mix.enable_history(limit.max(0.0) as usize);
+		Ok(Value::null())
+	})
+}
+
+/// Returns: a list of the mixture's recorded states, oldest first, each an associative list like `get_snapshot` returns.
+/// Empty if `enable_history` hasn't been used.
+#[hook("/datum/gas_mixture/proc/get_history")]
+fn _get_history_hook() {
+	let history_list = List::new();
+	for snapshot in with_mix(src, |mix| Ok(mix.history()))? {
+		history_list.append(snapshot_to_list(&snapshot)?);
+	}
+	Ok(Value::from(history_list))
+}
+
+/// Turns a snapshot into the associative list `get_snapshot` returns.
+fn snapshot_to_list(snapshot: &gas::mixture::MixtureSnapshot) -> Result<Value, Runtime> {
+	let gases_list = List::new();
+	for (idx, amt) in snapshot.enumerate() {
+		if amt > GAS_MIN_MOLES {
+			gases_list.set(gas_idx_to_id(idx)?, Value::from(amt))?;
+		}
+	}
+	let snapshot_list = List::new();
+	snapshot_list.set(
+		Value::from_string("temperature")?,
+		Value::from(snapshot.temperature),
+	)?;
+	snapshot_list.set(Value::from_string("volume")?, Value::from(snapshot.volume))?;
+	snapshot_list.set(
+		Value::from_string("pressure")?,
+		Value::from(snapshot.return_pressure()),
+	)?;
+	snapshot_list.set(
+		Value::from_string("heat_capacity")?,
+		Value::from(snapshot.heat_capacity),
+	)?;
+	snapshot_list.set(
+		Value::from_string("total_moles")?,
+		Value::from(snapshot.total_moles()),
+	)?;
+	snapshot_list.set(Value::from_string("gases")?, Value::from(gases_list))?;
+	Ok(Value::from(snapshot_list))
+}
+
+/// Saves the mixture's gases, temperature and volume, so they can be brought back with restore_archive.
+#[hook("/datum/gas_mixture/proc/archive")]
+fn _archive_hook() {
+	with_mix_mut(src, |mix| {
+		mix.archive();
+		Ok(Value::null())
+	})
+}
+
+/// Reverts the mixture to what it was when archive was last called.
+/// Returns: whether there was an archive to restore.
+#[hook("/datum/gas_mixture/proc/restore_archive")]
+fn _restore_archive_hook() {
+	with_mix_mut(src, |mix| Ok(Value::from(mix.restore_archive())))
+}
+
 /// Returns: a list of the gases in the mixture, associated with their IDs.
 #[hook("/datum/gas_mixture/proc/get_gases")]
 fn _get_gases_hook() {
-	with_mix(src, |mix| {
-		let gases_list: List = List::new();
-		mix.for_each_gas(|idx, gas| {
-			if gas > GAS_MIN_MOLES {
-				gases_list.append(gas_idx_to_id(idx)?);
-			}
-			Ok(())
-		})?;
-		Ok(Value::from(gases_list))
-	})
+	let present = with_mix(src, |mix| Ok(mix.present_gases()))?;
+	let gases_list: List = List::new();
+	for idx in present {
+		gases_list.append(gas_idx_to_id(idx)?);
+	}
+	Ok(Value::from(gases_list))
+}
+
+/// Returns: the mixture serialized as a base64 string, for saving between rounds. Load it with gas_mixture_from_bytes.
+#[hook("/datum/gas_mixture/proc/serialize_bytes")]
+fn _serialize_bytes_hook() {
+	let bytes = with_mix(src, |mix| Ok(mix.to_bytes()))?;
+	Value::from_string(base64::encode(bytes))
+}
+
+/// Args: (string, mixture). Loads a base64 string made by serialize_bytes into the given mixture, replacing its gases, temperature and volume.
+/// Returns: the mixture.
+#[hook("/proc/gas_mixture_from_bytes")]
+fn _gas_mixture_from_bytes_hook(string: Value, target: Value) {
+	let bytes = base64::decode(string.as_string()?)
+		.map_err(|_| runtime!("Serialized gas mixture is not valid base64!"))?;
+	let loaded = Mixture::from_bytes(&bytes)?;
+	with_mix_mut(target, |mix| {
+		mix.copy_from_mutable(&loaded);
+		mix.volume = loaded.volume;
+		Ok(())
+	})?;
+	Ok(target.clone())
+}
+
+/// Returns: the mixture as a JSON string, with its volume, temperature and gases by ID. Load it with gas_from_json.
+#[hook("/datum/gas_mixture/proc/to_json")]
+fn _to_json_hook() {
+	let json = with_mix(src, |mix| Ok(mix.to_json()))?;
+	Value::from_string(json)
+}
+
+/// Args: (text, mixture). Loads JSON made by to_json into the given mixture, replacing its gases, temperature and volume.
+/// Returns: the mixture.
+#[hook("/proc/gas_from_json")]
+fn _gas_from_json_hook(text: Value, target: Value) {
+	let loaded = Mixture::from_json(&text.as_string()?)?;
+	with_mix_mut(target, |mix| {
+		mix.copy_from_mutable(&loaded);
+		mix.volume = loaded.volume;
+		Ok(())
+	})?;
+	Ok(target.clone())
+}
+
+/// Args: (a, b, a_ratio, mixture, volume). Makes the argument mixture into a blend of `a_ratio` of `a` and the rest of `b`,
+/// with its temperature from their combined thermal energy. `a` and `b` aren't changed.
+/// The volume is `volume` if given, otherwise the larger of the two inputs'.
+/// Returns: the mixture.
+#[hook("/proc/auxmos_blend")]
+fn _blend_hook(a: Value, b: Value, a_ratio: Value, target: Value, volume: Value) {
+	let a_ratio = a_ratio.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if !a_ratio.is_finite() {
+		return Err(runtime!(
+			"Attempted to blend with a ratio that is NaN or infinite."
+		));
+	}
+	let volume = volume.as_number().ok().filter(|&v| v > 0.0);
+	let blended = with_mixes(a, b, |a_mix, b_mix| {
+		Ok(Mixture::blend(a_mix, b_mix, a_ratio, volume))
+	})?;
+	with_mix_mut(target, |mix| {
+		mix.copy_from(&blended, true);
+		Ok(())
+	})?;
+	Ok(target.clone())
+}
+
+/// Args: (a, b, mixture). Makes the argument mixture into however many more moles of each gas `a` has than `b`,
+/// at `a`'s temperature and volume. Gases `b` has more of are left out instead of going negative.
+/// `a` and `b` aren't changed.
+/// Returns: the mixture.
+#[hook("/proc/auxmos_gas_difference")]
+fn _gas_difference_hook(a: Value, b: Value, target: Value) {
+	let delta = with_mixes(a, b, |a_mix, b_mix| Ok(a_mix - b_mix))?;
+	with_mix_mut(target, |mix| {
+		mix.copy_from(&delta, true);
+		Ok(())
+	})?;
+	Ok(target.clone())
+}
+
+/// Returns: an associative list of the gas IDs in the mixture to their partial pressures, in kilopascals.
+#[hook("/datum/gas_mixture/proc/partial_pressures")]
+fn _partial_pressures_hook() {
+	let pressures = with_mix(src, |mix| Ok(mix.partial_pressures()))?;
+	let pressures_list = List::new();
+	for (idx, pressure) in pressures {
+		pressures_list.set(gas_idx_to_id(idx)?, Value::from(pressure))?;
+	}
+	Ok(Value::from(pressures_list))
 }
 
-/// Args: (temperature). Sets the temperature of the mixture. Will be set to 2.7 if it's too low.
+/// Args: (temperature). Sets the temperature of the mixture, keeping the moles as they are. Will be set to TCMB if it's too low.
 #[hook("/datum/gas_mixture/proc/set_temperature")]
 fn _set_temperature_hook(arg_temp: Value) {
 	let v = arg_temp.as_number().map_err(|_| {
@@ -183,7 +666,7 @@ fn _set_temperature_hook(arg_temp: Value) {
 	})?;
 	if v.is_finite() {
 		with_mix_mut(src, |mix| {
-			mix.set_temperature(v.max(2.7));
+			mix.set_temperature(v);
 			Ok(Value::null())
 		})
 	} else {
@@ -193,6 +676,24 @@ fn _set_temperature_hook(arg_temp: Value) {
 	}
 }
 
+/// Args: (min, max). Clamps the temperature of the mixture between min and max. Either may be omitted, defaulting to TCMB and MAX_TEMPERATURE respectively.
+#[hook("/datum/gas_mixture/proc/clamp_temperature")]
+fn _clamp_temperature_hook(min_arg: Value, max_arg: Value) {
+	let min = min_arg.as_number().unwrap_or(constants::TCMB);
+	let max = max_arg.as_number().unwrap_or(constants::MAX_TEMPERATURE);
+	if !min.is_normal() || !max.is_normal() || min > max {
+		return Err(runtime!(
+			"Invalid bounds for clamp_temperature: {} to {}",
+			min,
+			max
+		));
+	}
+	with_mix_mut(src, |mix| {
+		mix.clamp_temperature(min, max);
+		Ok(Value::null())
+	})
+}
+
 /// Args: (gas_id). Returns the heat capacity from the given gas, in J/K (probably).
 #[hook("/datum/gas_mixture/proc/partial_heat_capacity")]
 fn _partial_heat_capacity(gas_id: Value) {
@@ -203,6 +704,18 @@ fn _partial_heat_capacity(gas_id: Value) {
 	})
 }
 
+/// Returns: an associative list of the IDs of the gases in the mix to how much heat capacity each of them gives it, in J/K.
+#[hook("/datum/gas_mixture/proc/heat_capacity_breakdown")]
+fn _heat_capacity_breakdown_hook() {
+	with_mix(src, |mix| {
+		let breakdown = List::new();
+		for (idx, cap) in mix.heat_capacity_breakdown() {
+			breakdown.set(gas_idx_to_id(idx)?, Value::from(cap))?;
+		}
+		Ok(Value::from(breakdown))
+	})
+}
+
 /// Args: (volume). Sets the volume of the gas.
 #[hook("/datum/gas_mixture/proc/set_volume")]
 fn _set_volume_hook(vol_arg: Value) {
@@ -214,35 +727,230 @@ fn _set_volume_hook(vol_arg: Value) {
 			std::column!()
 		)
 	})?;
-	with_mix_mut(src, |mix| {
-		mix.volume = volume;
-		Ok(Value::null())
-	})
+	with_mix_mut(src, |mix| {
+		mix.volume = volume;
+		Ok(Value::null())
+	})
+}
+
+/// Args: (volume). Sets the volume of the gas while keeping its moles and temperature, changing its pressure. Volume must be positive.
+#[hook("/datum/gas_mixture/proc/set_volume_keep_moles")]
+fn _set_volume_keep_moles_hook(vol_arg: Value) {
+	let volume = vol_arg.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if !(volume > 0.0 && volume.is_finite()) {
+		return Err(runtime!(
+			"Attempted to set a gas mixture's volume to {}, which is not positive.",
+			volume
+		));
+	}
+	with_mix_mut(src, |mix| {
+		mix.set_volume_preserve_moles(volume);
+		Ok(Value::null())
+	})
+}
+
+/// Args: (gas_id). Returns: the amount of substance of the given gas, in moles.
+#[hook("/datum/gas_mixture/proc/get_moles")]
+fn _get_moles_hook(gas_id: Value) {
+	with_mix(src, |mix| {
+		Ok(Value::from(mix.get_moles_safe(gas_idx_from_value(gas_id)?)))
+	})
+}
+
+/// Args: (gas_id). Returns: the fraction of the mix's pressure contributed by the given gas, from 0 to 1.
+#[hook("/datum/gas_mixture/proc/partial_pressure_fraction")]
+fn _partial_pressure_fraction_hook(gas_id: Value) {
+	with_mix(src, |mix| {
+		Ok(Value::from(
+			mix.get_breathable_fraction(gas_idx_from_value(gas_id)?),
+		))
+	})
+}
+
+/// Args: (gas_id, moles). Sets the amount of substance of the given gas, in moles.
+#[hook("/datum/gas_mixture/proc/set_moles")]
+fn _set_moles_hook(gas_id: Value, amt_val: Value) {
+	let vf = amt_val.as_number()?;
+	if !vf.is_finite() {
+		return Err(runtime!("Attempted to set moles to NaN or infinity."));
+	}
+	if vf < 0.0 {
+		return Err(runtime!("Attempted to set moles to a negative number."));
+	}
+	with_mix_mut(src, |mix| {
+		mix.set_moles(gas_idx_from_value(gas_id)?, vf);
+		Ok(Value::null())
+	})
+}
+/// Args: (list). Takes an associative list of gas IDs to moles and sets all of them at once. If any entry is invalid, nothing is set and all invalid IDs are reported.
+#[hook("/datum/gas_mixture/proc/set_moles_bulk")]
+fn _set_moles_bulk_hook(gas_list: Value) {
+	let gases = gas_list.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut entries = Vec::with_capacity(gases.len() as usize);
+	let mut invalid_ids = Vec::new();
+	for i in 1..=gases.len() {
+		let gas_id = gases.get(i)?;
+		let id_string = gas_id
+			.as_string()
+			.unwrap_or_else(|_| "(non-string)".to_string());
+		match gas_idx_from_value(&gas_id) {
+			Ok(idx) => {
+				let amt = gases.get(gas_id)?.as_number().unwrap_or(-1.0);
+				if amt.is_finite() && amt >= 0.0 {
+					entries.push((idx, amt));
+				} else {
+					invalid_ids.push(id_string);
+				}
+			}
+			Err(_) => invalid_ids.push(id_string),
+		}
+	}
+	if !invalid_ids.is_empty() {
+		return Err(runtime!(
+			"Invalid entries in set_moles_bulk: {}",
+			invalid_ids.join(", ")
+		));
+	}
+	with_mix_mut(src, |mix| {
+		mix.set_moles_bulk(&entries);
+		Ok(Value::null())
+	})
+}
+
+/// Reads an associative list of gas IDs to numbers into a vec of gas indices and those numbers.
+fn gas_amounts_from_list(list_value: &Value) -> Result<Vec<(usize, f32)>, Runtime> {
+	let list = list_value.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut amounts = Vec::with_capacity(list.len() as usize);
+	for i in 1..=list.len() {
+		let gas_id = list.get(i)?;
+		let idx = gas_idx_from_value(&gas_id)?;
+		let amt = list.get(gas_id)?.as_number().map_err(|_| {
+			runtime!(
+				"Attempt to interpret non-number value as number {} {}:{}",
+				std::file!(),
+				std::line!(),
+				std::column!()
+			)
+		})?;
+		amounts.push((idx, amt));
+	}
+	Ok(amounts)
+}
+
+/// Args: (mixture, volume, pressure, temperature, composition). Composition is an associative list of gas IDs to ratios,
+/// which don't need to add up to 1. Makes the argument mixture into a mixture of that volume and temperature,
+/// holding enough gas, split by the ratios, to be at the given pressure.
+/// Returns: the mixture.
+#[hook("/proc/auxmos_gas_from_pressure")]
+fn _gas_from_pressure_hook(
+	target: Value,
+	volume: Value,
+	pressure: Value,
+	temperature: Value,
+	composition: Value,
+) {
+	let volume = volume.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let pressure = pressure.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let temperature = temperature.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let composition = gas_amounts_from_list(composition)?;
+	let mix = Mixture::from_pressure(volume, pressure, temperature, &composition)?;
+	with_mix_mut(target, |target_mix| {
+		target_mix.copy_from(&mix, true);
+		Ok(())
+	})?;
+	Ok(target.clone())
 }
 
-/// Args: (gas_id). Returns: the amount of substance of the given gas, in moles.
-#[hook("/datum/gas_mixture/proc/get_moles")]
-fn _get_moles_hook(gas_id: Value) {
+/// Args: (list). Takes an associative list of gas IDs to moles.
+/// Returns: whether the mixture has at least that many moles of every gas in the list.
+#[hook("/datum/gas_mixture/proc/meets_requirements")]
+fn _meets_requirements_hook(reqs: Value) {
+	let requirements = gas_amounts_from_list(reqs)?;
 	with_mix(src, |mix| {
-		Ok(Value::from(mix.get_moles(gas_idx_from_value(gas_id)?)))
+		Ok(Value::from(mix.contains_at_least(&requirements)))
 	})
 }
 
-/// Args: (gas_id, moles). Sets the amount of substance of the given gas, in moles.
-#[hook("/datum/gas_mixture/proc/set_moles")]
-fn _set_moles_hook(gas_id: Value, amt_val: Value) {
-	let vf = amt_val.as_number()?;
-	if !vf.is_finite() {
-		return Err(runtime!("Attempted to set moles to NaN or infinity."));
-	}
-	if vf < 0.0 {
-		return Err(runtime!("Attempted to set moles to a negative number."));
-	}
-	with_mix_mut(src, |mix| {
-		mix.set_moles(gas_idx_from_value(gas_id)?, vf);
+/// Args: (mixture, list). Merges the mixture into this one, but caps each gas in the associative list of gas IDs to moles at that amount.
+/// The given mixture is replaced by whatever was over the caps, at the merged temperature.
+#[hook("/datum/gas_mixture/proc/merge_capped")]
+fn _merge_capped_hook(other: Value, caps: Value) {
+	let caps = gas_amounts_from_list(caps)?;
+	with_mixes_mut(src, other, |src_mix, other_mix| {
+		let overflow = src_mix.merge_capped(other_mix, &caps);
+		other_mix.copy_from_mutable(&overflow);
 		Ok(Value::null())
 	})
 }
+
+/// Returns: list("id" = the ID of the gas with the most moles, "fraction" = its mole fraction), or null if the mixture is empty.
+#[hook("/datum/gas_mixture/proc/dominant_gas")]
+fn _dominant_gas_hook() {
+	match with_mix(src, |mix| Ok(mix.dominant_gas()))? {
+		Some((idx, fraction)) => {
+			let dominant = List::new();
+			dominant.set(Value::from_string("id")?, gas_idx_to_id(idx)?)?;
+			dominant.set(Value::from_string("fraction")?, Value::from(fraction))?;
+			Ok(Value::from(dominant))
+		}
+		None => Ok(Value::null()),
+	}
+}
+
+/// Returns: a list of the IDs of the gases in the mixture that are enough to be seen, going by each gas's `moles_visible`.
+#[hook("/datum/gas_mixture/proc/visible_gases")]
+fn _visible_gases_hook() {
+	let thresholds = types::visibility_thresholds();
+	let visible = with_mix(src, |mix| Ok(mix.visible_gases(&thresholds)))?;
+	let gases_list = List::new();
+	for idx in visible {
+		gases_list.append(gas_idx_to_id(idx)?);
+	}
+	Ok(Value::from(gases_list))
+}
+
 /// Args: (gas_id, moles). Adjusts the given gas's amount by the given amount, e.g. (GAS_O2, -0.1) will remove 0.1 moles of oxygen from the mixture.
 #[hook("/datum/gas_mixture/proc/adjust_moles")]
 fn _adjust_moles_hook(id_val: Value, num_val: Value) {
@@ -322,6 +1030,32 @@ fn _subtract_hook(num_val: Value) {
 	})
 }
 
+/// Args: (target, max_delta). Moves src's pressure toward the target pressure by at most max_delta kilopascals, for cycling airlocks.
+/// Gas is deleted or made from nothing to do it, scaling every gas alike, so src keeps its makeup and temperature; an empty src can't be filled.
+/// Returns: whether src is now at the target pressure.
+#[hook("/datum/gas_mixture/proc/step_to_pressure")]
+fn _step_to_pressure_hook(target: Value, max_delta: Value) {
+	let target = target.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let max_delta = max_delta.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mix_mut(src, |mix| {
+		Ok(Value::from(mix.step_toward_pressure(target, max_delta)))
+	})
+}
+
 ///Args: (coefficient). Multiplies all gases by this amount.
 #[hook("/datum/gas_mixture/proc/multiply")]
 fn _multiply_hook(num_val: Value) {
@@ -342,6 +1076,24 @@ fn _divide_hook(num_val: Value) {
 	})
 }
 
+/// Args: (gas_id, moles, mixture). Moves up to the given moles of just that gas from src into the argument mixture, along with its thermal energy.
+/// Returns: the moles actually moved, which is all src had of it if that's less.
+#[hook("/datum/gas_mixture/proc/transfer_gas")]
+fn _transfer_gas_hook(gas_id: Value, moles: Value, dest: Value) {
+	let idx = gas_idx_from_value(gas_id)?;
+	let moles = moles.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mixes_mut(src, dest, |src_mix, dest_mix| {
+		Ok(Value::from(src_mix.transfer_gas_to(idx, moles, dest_mix)))
+	})
+}
+
 ///Args: (mixture, flag, amount). Takes `amount` from src that have the given `flag` and puts them into the given `mixture`. Returns: 0 if gas didn't have any with that flag, 1 if it did.
 #[hook("/datum/gas_mixture/proc/__remove_by_flag")]
 fn _remove_by_flag_hook(into: Value, flag_val: Value, amount_val: Value) {
@@ -386,9 +1138,9 @@ fn get_by_flag_hook(flag_val: Value) {
 	})
 }
 
-/// Args: (mixture, ratio, gas_list). Takes gases given by `gas_list` and moves `ratio` amount of those gases from `src` into `mixture`.
+/// Args: (mixture, gas_list, ratio). Takes gases given by `gas_list` and moves `ratio` amount of those gases from `src` into `mixture`.
 #[hook("/datum/gas_mixture/proc/scrub_into")]
-fn _scrub_into_hook(into: Value, ratio_v: Value, gas_list: Value) {
+fn _scrub_into_hook(into: Value, gas_list: Value, ratio_v: Value) {
 	let ratio = ratio_v.as_number().map_err(|_| {
 		runtime!(
 			"Attempt to interpret non-number value as number {} {}:{}",
@@ -412,7 +1164,7 @@ fn _scrub_into_hook(into: Value, ratio_v: Value, gas_list: Value) {
 		.filter_map(|idx| gas_idx_from_value(&gases_to_scrub.get(idx).unwrap()).ok())
 		.collect::<Vec<_>>();
 	with_mixes_mut(src, into, |src_gas, dest_gas| {
-		src_gas.transfer_gases_to(ratio, &gas_scrub_vec, dest_gas);
+		src_gas.scrub_into(&gas_scrub_vec, dest_gas, ratio);
 		Ok(Value::from(true))
 	})
 }
@@ -426,6 +1178,17 @@ fn _mark_immutable_hook() {
 	})
 }
 
+/// Args: (enabled). Sets whether reactions can happen in this mix at all; they can by default.
+/// Copying the mix with `copy_from` copies this too.
+#[hook("/datum/gas_mixture/proc/set_reactions_enabled")]
+fn _set_reactions_enabled_hook(enabled: Value) {
+	let suppressed = enabled.as_number().map_or(false, |n| n == 0.0);
+	with_mix_mut(src, |mix| {
+		mix.set_reactions_suppressed(suppressed);
+		Ok(Value::null())
+	})
+}
+
 /// Clears the gas mixture my removing all of its gases.
 #[hook("/datum/gas_mixture/proc/clear")]
 fn _clear_hook() {
@@ -446,6 +1209,42 @@ fn _compare_hook(other: Value) {
 	})
 }
 
+/// Args: (mixture, moles_tol, temp_tol). Returns: whether every gas is within moles_tol moles of the other mixture's, and the temperatures within temp_tol kelvin.
+#[hook("/datum/gas_mixture/proc/approx_equals")]
+fn _approx_equals_hook(other: Value, moles_tol: Value, temp_tol: Value) {
+	let moles_tol = moles_tol.as_number().unwrap_or(GAS_MIN_MOLES);
+	let temp_tol = temp_tol.as_number().unwrap_or_default();
+	with_mixes(src, other, |src_mix, other_mix| {
+		Ok(Value::from(
+			src_mix.approx_eq(other_mix, moles_tol, temp_tol),
+		))
+	})
+}
+
+/// Args: (mixture). Returns: list("id" = the gas ID with the biggest difference in moles, or -1 if the temperature differs more, "amount" = the difference).
+#[hook("/datum/gas_mixture/proc/max_difference")]
+fn _max_difference_hook(other: Value) {
+	let (gas, amount) = with_mixes(src, other, |src_mix, other_mix| {
+		Ok(src_mix.max_difference(other_mix))
+	})?;
+	let difference = List::new();
+	difference.set(
+		Value::from_string("id")?,
+		match gas {
+			Some(idx) => gas_idx_to_id(idx)?,
+			None => Value::from(-1.0),
+		},
+	)?;
+	difference.set(Value::from_string("amount")?, Value::from(amount))?;
+	Ok(Value::from(difference))
+}
+
+/// Returns: the moles of fuel fires burned in src during the last finished reaction tick, for combustion gauges; 0 if nothing burned.
+#[hook("/datum/gas_mixture/proc/last_fuel_burnt")]
+fn _last_fuel_burnt_hook() {
+	with_mix(src, |mix| Ok(Value::from(mix.get_fuel_burnt_last_tick())))
+}
+
 /// Args: (holder). Runs all reactions on this gas mixture. Holder is used by the reactions, and can be any arbitrary datum or null.
 #[hook("/datum/gas_mixture/proc/react")]
 fn _react_hook(holder: Value) {
@@ -464,7 +1263,144 @@ fn _react_hook(holder: Value) {
 	Ok(Value::from(ret.bits() as f32))
 }
 
+/// Args: (holder, list). As react(), but only the reactions whose IDs are in the list can fire.
+/// An empty list allows every reaction, same as react(), rather than none.
+/// Returns: the combined reaction flags.
+#[hook("/datum/gas_mixture/proc/react_only")]
+fn _react_only_hook(holder: Value, reaction_ids: Value) {
+	let ids_list = reaction_ids.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut allowed = std::collections::HashSet::with_capacity(ids_list.len() as usize);
+	for i in 1..=ids_list.len() {
+		allowed.insert(reaction_id_from_string(&ids_list.get(i)?.as_string()?));
+	}
+	Ok(Value::from(
+		react_allowed(src, holder, &allowed)?.bits() as f32
+	))
+}
+
+/// Removes trace amounts of gas from the mixture, without changing its temperature.
+/// Returns: whether there were any to remove.
+#[hook("/datum/gas_mixture/proc/gc")]
+fn _gc_hook() {
+	with_mix_mut(src, |mix| Ok(Value::from(mix.gc_traces())))
+}
+
+/// Args: (epsilon). Removes gases with less than `epsilon` moles and snaps the temperature to the grid `content_hash` uses,
+/// so idle mixtures keep the same content hash. Best called on mixtures that aren't changing.
+/// Idle turfs are already normalized every run, with the minimum moles as `epsilon`.
+#[hook("/datum/gas_mixture/proc/normalize")]
+fn _normalize_hook(epsilon: Value) {
+	let epsilon = epsilon.as_number().unwrap_or(GAS_MIN_MOLES);
+	with_mix_mut(src, |mix| {
+		mix.normalize(epsilon);
+		Ok(Value::null())
+	})
+}
+
+/// Args: (holder). Runs the reactions the mixture can do, then undoes them, to see what they would do.
+/// Nothing is recorded in stats, history or cooldowns, and hooked reactions leave the holder alone;
+/// DM reactions can check auxmos_is_previewing() to do the same.
+/// Returns: a list with an associative list for each reaction, in order, with the reaction's "id",
+/// "gases", an associative list of gas IDs to the moles made (negative if used up), and "temperature", the change in temperature.
+#[hook("/datum/gas_mixture/proc/react_preview")]
+fn _react_preview_hook(holder: Value) {
+	let reports_list = List::new();
+	for report in react_preview(src, holder)? {
+		let gases_list = List::new();
+		for (idx, delta) in report.gas_deltas {
+			gases_list.set(gas_idx_to_id(idx)?, Value::from(delta))?;
+		}
+		let report_list = List::new();
+		report_list.set(Value::from_string("id")?, reaction_string_id(report.id)?)?;
+		report_list.set(Value::from_string("gases")?, Value::from(gases_list))?;
+		report_list.set(
+			Value::from_string("temperature")?,
+			Value::from(report.temperature_delta),
+		)?;
+		reports_list.append(Value::from(report_list));
+	}
+	Ok(Value::from(reports_list))
+}
+
+/// Args: (holder, ambient_pressure, open_to_space). As react(), but reactions with MIN_PRESSURE or ENCLOSED requirements
+/// check them against the given pressure, or the mixture's own if it's null, and whether the holder is open to space.
+/// Returns: the combined reaction flags.
+#[hook("/datum/gas_mixture/proc/react_in_context")]
+fn _react_in_context_hook(holder: Value, ambient_pressure: Value, open_to_space: Value) {
+	let env = ReactionEnv {
+		ambient_pressure: ambient_pressure.as_number().ok(),
+		open_to_space: open_to_space.as_number().map_or(false, |n| n != 0.0),
+	};
+	Ok(Value::from(
+		react_in_context(src, holder, &env)?.bits() as f32
+	))
+}
+
+/// Returns: a hash of the mixture's gases and temperature, as a string to avoid losing precision. Mixtures differing only by tiny amounts hash identically.
+#[hook("/datum/gas_mixture/proc/get_content_hash")]
+fn _content_hash_hook() {
+	with_mix(src, |mix| {
+		Value::from_string(mix.content_hash().to_string())
+	})
+}
+
+/// Args: (holder). As react(), but returns a list of the IDs of every reaction that reacted.
+#[hook("/datum/gas_mixture/proc/react_report")]
+fn _react_report_hook(holder: Value) {
+	let (_, fired) = react_with_report(src, holder)?;
+	let fired_list = List::new();
+	for reaction in fired {
+		fired_list.append(reaction_string_id(reaction)?);
+	}
+	Ok(Value::from(fired_list))
+}
+
+/// Args: (holder). As react(), but returns a list with an associative list for each reaction that reacted and has effects,
+/// with the reaction's "id", and "light", the light intensity, and "sound" and "volume", if it has those.
+/// Light and volume are the reaction's `effect_light` and `effect_sound_volume` times the moles of gas it changed,
+/// with volume capped at 100.
+#[hook("/datum/gas_mixture/proc/react_with_effects")]
+fn _react_with_effects_hook(holder: Value) {
+	let (_, hints) = react_with_effects(src, holder)?;
+	let hints_list = List::new();
+	for hint in hints {
+		let hint_list = List::new();
+		hint_list.set(Value::from_string("id")?, reaction_string_id(hint.id)?)?;
+		if let Some(light) = hint.light {
+			hint_list.set(Value::from_string("light")?, Value::from(light))?;
+		}
+		if let Some((sound, volume)) = hint.sound {
+			hint_list.set(Value::from_string("sound")?, Value::from_string(&*sound)?)?;
+			hint_list.set(Value::from_string("volume")?, Value::from(volume))?;
+		}
+		hints_list.append(Value::from(hint_list));
+	}
+	Ok(Value::from(hints_list))
+}
+
+/// Args: (holder, max_iters). Runs all reactions repeatedly until nothing reacts anymore, up to max_iters passes (default 5).
+/// Returns: the amount of passes run. Hitting max_iters means the mixture may still be reacting.
+#[hook("/datum/gas_mixture/proc/react_stable")]
+fn _react_stable_hook(holder: Value, max_iters: Value) {
+	let max_iters = max_iters
+		.as_number()
+		.map_or(constants::DEFAULT_MAX_REACTION_PASSES, |n| {
+			n.max(0.0) as usize
+		});
+	Ok(Value::from(
+		react_until_stable(src, holder, max_iters)? as f32
+	))
+}
+
 /// Args: (heat). Adds a given amount of heat to the mixture, i.e. in joules taking into account capacity.
+/// Negative amounts remove heat, down to TCMB. Does nothing to an empty mixture.
 #[hook("/datum/gas_mixture/proc/adjust_heat")]
 fn _adjust_heat_hook() {
 	with_mix_mut(src, |mix| {
@@ -517,6 +1453,46 @@ fn _transfer_ratio_hook(other: Value, ratio: Value) {
 	})
 }
 
+/// Args: (mixture, pressure). Moves gas from `src` into `mixture` until `mixture` is at the given pressure in kilopascals, or `src` runs out.
+/// Returns: the amount of moles moved.
+#[hook("/datum/gas_mixture/proc/pump_to_pressure")]
+fn _pump_to_pressure_hook(output: Value, target: Value) {
+	let target_pressure = target.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mixes_mut(src, output, |src_mix, output_mix| {
+		Ok(Value::from(
+			src_mix.pump_to_pressure(output_mix, target_pressure),
+		))
+	})
+}
+
+/// Args: (mixture, pressure). Lets gas flow from `src` into `mixture` like a canister releasing it,
+/// until `mixture` is at the given pressure in kilopascals or both are at the same pressure.
+/// Does nothing if `mixture` is already at or above either.
+/// Returns: the amount of moles released.
+#[hook("/datum/gas_mixture/proc/canister_release")]
+fn _canister_release_hook(output: Value, pressure: Value) {
+	let release_pressure = pressure.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	with_mixes_mut(src, output, |src_mix, output_mix| {
+		Ok(Value::from(
+			src_mix.release_to(output_mix, release_pressure),
+		))
+	})
+}
+
 /// Args: (mixture). Makes `src` a copy of `mixture`, with volumes taken into account.
 #[hook("/datum/gas_mixture/proc/equalize_with")]
 fn _equalize_with_hook(total: Value) {
@@ -534,14 +1510,12 @@ fn _equalize_with_hook(total: Value) {
 #[hook("/datum/gas_mixture/proc/get_fuel_amount")]
 fn _fuel_amount_hook(temp: Value) {
 	with_mix(src, |air| {
-		Ok(Value::from(temp.as_number().ok().map_or_else(
-			|| air.get_fuel_amount(),
-			|new_temp| {
-				let mut test_air = air.copy_to_mutable();
-				test_air.set_temperature(new_temp);
-				test_air.get_fuel_amount()
-			},
-		)))
+		let temp = temp
+			.as_number()
+			.ok()
+			.filter(|t| t.is_normal())
+			.unwrap_or_else(|| air.get_temperature());
+		Ok(Value::from(air.get_fuel_amount(temp)))
 	})
 }
 
@@ -549,14 +1523,12 @@ fn _fuel_amount_hook(temp: Value) {
 #[hook("/datum/gas_mixture/proc/get_oxidation_power")]
 fn _oxidation_power_hook(temp: Value) {
 	with_mix(src, |air| {
-		Ok(Value::from(temp.as_number().ok().map_or_else(
-			|| air.get_oxidation_power(),
-			|new_temp| {
-				let mut test_air = air.clone();
-				test_air.set_temperature(new_temp);
-				test_air.get_oxidation_power()
-			},
-		)))
+		let temp = temp
+			.as_number()
+			.ok()
+			.filter(|t| t.is_normal())
+			.unwrap_or_else(|| air.get_temperature());
+		Ok(Value::from(air.get_oxidation_power(temp)))
 	})
 }
 
@@ -658,6 +1630,314 @@ fn _hook_max_gas_mixes() {
 	Ok(Value::from(tot_gases() as f32))
 }
 
+/// Returns: an associative list of the amount of gas mixtures in use ("used"), free slots ("free"), allocated slots ("capacity") and the most gas mixtures ever in use at once ("high_water_mark").
+#[hook("/proc/auxmos_gas_stats")]
+fn _hook_gas_stats() {
+	let (used, free, capacity) = GasArena::occupancy_stats();
+	let stats = List::new();
+	stats.set(Value::from_string("used")?, Value::from(used as f32))?;
+	stats.set(Value::from_string("free")?, Value::from(free as f32))?;
+	stats.set(
+		Value::from_string("capacity")?,
+		Value::from(capacity as f32),
+	)?;
+	stats.set(
+		Value::from_string("high_water_mark")?,
+		Value::from(GasArena::high_water_mark() as f32),
+	)?;
+	Ok(Value::from(stats))
+}
+
+/// Args: (sources, destinations, ratios). Three lists of the same length: the gas mixtures to move gas out of,
+/// the gas mixtures to move it into, and the ratio of each source's gas to move. Done in order, all at once.
+#[hook("/proc/auxmos_transfer_batch")]
+fn _hook_transfer_batch(sources: Value, destinations: Value, ratios: Value) {
+	let as_list = |list: &Value| {
+		list.as_list().map_err(|_| {
+			runtime!(
+				"Attempt to interpret non-list value as list {} {}:{}",
+				std::file!(),
+				std::line!(),
+				std::column!()
+			)
+		})
+	};
+	let (sources, destinations, ratios) =
+		(as_list(sources)?, as_list(destinations)?, as_list(ratios)?);
+	if sources.len() != destinations.len() || sources.len() != ratios.len() {
+		return Err(runtime!(
+			"Batch transfer lists have different lengths: {}, {}, {}",
+			sources.len(),
+			destinations.len(),
+			ratios.len()
+		));
+	}
+	let mut ops = Vec::with_capacity(sources.len() as usize);
+	for i in 1..=sources.len() {
+		let ratio = ratios.get(i)?.as_number().map_err(|_| {
+			runtime!(
+				"Attempt to interpret non-number value as number {} {}:{}",
+				std::file!(),
+				std::line!(),
+				std::column!()
+			)
+		})?;
+		ops.push((
+			mix_id(&sources.get(i)?)?,
+			mix_id(&destinations.get(i)?)?,
+			ratio,
+		));
+	}
+	GasArena::transfer_batch(&ops)?;
+	Ok(Value::null())
+}
+
+/// Args: (enabled). Turns checking every heat capacity cache against a full recalculation on if `enabled` is a nonzero number, off otherwise.
+/// It's off until turned on. Mismatches are reported as runtimes. Only there when built with the cache_audit feature.
+#[cfg(feature = "cache_audit")]
+#[hook("/proc/auxmos_enable_cache_audit")]
+fn _hook_enable_cache_audit(enabled: Value) {
+	gas::mixture::set_cache_audit(enabled.as_number().map_or(false, |n| n != 0.0));
+	Ok(Value::null())
+}
+
+/// Returns: an associative list of gas IDs to associative lists of the moles of that gas "produced" and "consumed"
+/// server-wide during the last turf processing tick, by reactions and planetary atmos.
+/// Nothing is recorded until this is first called, so that call's list is empty.
+#[hook("/proc/auxmos_tick_gas_stats")]
+fn _hook_tick_gas_stats() {
+	gas::stats::enable();
+	let stats_list = List::new();
+	for (idx, (produced, consumed)) in gas::stats::last_tick_stats().into_iter().enumerate() {
+		let gas_list = List::new();
+		gas_list.set(Value::from_string("produced")?, Value::from(produced))?;
+		gas_list.set(Value::from_string("consumed")?, Value::from(consumed))?;
+		stats_list.set(gas_idx_to_id(idx)?, Value::from(gas_list))?;
+	}
+	Ok(Value::from(stats_list))
+}
+
+/// Args: (slots). Sets how many gas slots past the number of registered gases mixtures reserve when they grow,
+/// so registering gases mid-round doesn't reallocate every mixture. Best set before gases are registered.
+#[hook("/proc/auxmos_set_gas_vector_headroom")]
+fn _hook_set_gas_vector_headroom(slots: Value) {
+	let slots = slots.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	gas::mixture::set_gas_vector_headroom(slots.max(0.0) as usize);
+	Ok(Value::null())
+}
+
+/// Returns: the total thermal energy of every gas mixture in use, in joules.
+#[hook("/proc/auxmos_total_thermal_energy")]
+fn _hook_total_thermal_energy() {
+	Ok(Value::from(GasArena::total_thermal_energy() as f32))
+}
+
+/// Args: (list). Takes a list of gas mixtures.
+/// Returns: the average temperature of the mixtures, weighted by heat capacity. Empty mixtures are ignored; if they're all empty, TCMB.
+#[hook("/proc/auxmos_average_temperature")]
+fn _hook_average_temperature(mixtures: Value) {
+	let mixtures_list = mixtures.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut ids = Vec::with_capacity(mixtures_list.len() as usize);
+	for i in 1..=mixtures_list.len() {
+		ids.push(mix_id(&mixtures_list.get(i)?)?);
+	}
+	Ok(Value::from(GasArena::average_temperature(&ids)))
+}
+
+/// Args: (list). Takes a list of turfs.
+/// Returns: a list of the pressures of the turfs' air, in the same order. Empty air is 0.
+#[hook("/proc/auxmos_turf_pressures")]
+fn _hook_turf_pressures(turfs: Value) {
+	let turfs_list = turfs.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut ids = Vec::with_capacity(turfs_list.len() as usize);
+	for i in 1..=turfs_list.len() {
+		ids.push(mix_id(&turfs_list.get(i)?.get(byond_string!("air"))?)?);
+	}
+	let pressures = List::new();
+	for pressure in GasArena::pressures_for(&ids) {
+		pressures.append(Value::from(pressure));
+	}
+	Ok(Value::from(pressures))
+}
+
+/// Args: (list). Takes a list of turfs, e.g. every turf on one z-level. Turfs without air are skipped.
+/// Returns: an associative list of gas IDs to the total moles of that gas in all of the turfs' air.
+#[hook("/proc/auxmos_sum_gases")]
+fn _hook_sum_gases(turfs: Value) {
+	let turfs_list = turfs.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut ids = Vec::with_capacity(turfs_list.len() as usize);
+	for i in 1..=turfs_list.len() {
+		if let Ok(id) = turfs_list
+			.get(i)?
+			.get(byond_string!("air"))
+			.and_then(|air| mix_id(&air))
+		{
+			ids.push(id);
+		}
+	}
+	let totals = List::new();
+	for (idx, total) in GasArena::sum_gases(&ids) {
+		totals.set(gas_idx_to_id(idx)?, Value::from(total as f32))?;
+	}
+	Ok(Value::from(totals))
+}
+
+/// Args: (list). Takes a list of gas mixtures; nulls are allowed.
+/// Returns: all of them serialized together as one base64 string, for saving between rounds. Load it with auxmos_deserialize_mixtures.
+#[hook("/proc/auxmos_serialize_mixtures")]
+fn _hook_serialize_mixtures(mixtures: Value) {
+	let mixtures_list = mixtures.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut ids = Vec::with_capacity(mixtures_list.len() as usize);
+	for i in 1..=mixtures_list.len() {
+		ids.push(mix_id(&mixtures_list.get(i)?).unwrap_or(usize::MAX));
+	}
+	Value::from_string(base64::encode(GasArena::serialize_all(&ids)))
+}
+
+/// Args: (string, list). Loads a base64 string made by auxmos_serialize_mixtures into the given gas mixtures, in the same order.
+/// Mixtures that were empty when saved are cleared. Extra entries on either side are ignored.
+/// Returns: the amount of mixtures saved in the string.
+#[hook("/proc/auxmos_deserialize_mixtures")]
+fn _hook_deserialize_mixtures(string: Value, targets: Value) {
+	let bytes = base64::decode(string.as_string()?)
+		.map_err(|_| runtime!("Serialized gas mixtures are not valid base64!"))?;
+	let loaded = GasArena::deserialize_all(&bytes)?;
+	let targets_list = targets.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	for (i, mix) in (1..=targets_list.len()).zip(&loaded) {
+		let target = targets_list.get(i)?;
+		if mix_id(&target).is_err() {
+			continue;
+		}
+		with_mix_mut(&target, |target_mix| {
+			match mix {
+				Some(mix) => {
+					target_mix.copy_from_mutable(mix);
+					target_mix.volume = mix.volume;
+				}
+				None => target_mix.clear(),
+			}
+			Ok(())
+		})?;
+	}
+	Ok(Value::from(loaded.len() as f32))
+}
+
+/// Reacts every turf's air that can react. Reactions hooked in Rust run in parallel; ones defined in DM,
+/// and anything the hooked ones do to the turf, like exposing it to fire, run on the main thread after.
+/// Gas mixtures that aren't a turf's air are skipped, since there's no telling what holds them.
+/// Returns: the amount of gas mixtures that reacted.
+#[cfg(feature = "turf_processing")]
+#[hook("/proc/auxmos_react_all")]
+fn _hook_react_all() {
+	let turfs = turfs::turf_air_holders();
+	Ok(Value::from(GasArena::parallel_react_all(|idx| {
+		let turf = unsafe { Value::turf_by_id_unchecked(*turfs.get(&idx)?) };
+		Some((turf.get(byond_string!("air")).ok()?, turf))
+	})? as f32))
+}
+
+/// Frees any unused gas mixtures at the end of the arena. Best called when not much is going on.
+/// Returns: the amount of gas mixtures removed.
+#[hook("/proc/auxmos_shrink_gas_pool")]
+fn _hook_shrink_gas_pool() {
+	Ok(Value::from(GasArena::shrink_to_fit() as f32))
+}
+
+/// Args: (reads). Benchmarks reading src the given amount of times, first as usual, then as if workers were running,
+/// so every read takes the mixture's lock as well as the arena's. Run it while turf processing is idle,
+/// or both runs take every lock.
+/// Returns: list("fast_ms", "fast_locks", "locked_ms", "locked_locks"): how long each run took and how many locks it took.
+#[hook("/datum/gas_mixture/proc/__benchmark_reads")]
+fn _benchmark_reads_hook(reads: Value) {
+	let reads = reads.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})? as usize;
+	let id = mix_id(src)?;
+	let time_reads = || -> Result<(f32, usize), Runtime> {
+		// the arena's always locked, the mixture only if its lock can't be skipped
+		let locks_per_read = if GasArena::read_skips_mix_lock(id) {
+			1
+		} else {
+			2
+		};
+		let start = std::time::Instant::now();
+		let mut total = 0.0;
+		for _ in 0..reads {
+			total += GasArena::with_gas_mixture(id, |mix| Ok(mix.get_temperature()))?;
+		}
+		let elapsed = start.elapsed().as_secs_f32() * 1000.0;
+		// so the reads can't be optimized out
+		if total.is_nan() {
+			return Err(runtime!("Gas mixture {} has a NaN temperature!", id));
+		}
+		Ok((elapsed, reads * locks_per_read))
+	};
+	let (fast_ms, fast_locks) = time_reads()?;
+	let phase = gas::WorkerPhase::begin();
+	let locked = time_reads();
+	drop(phase);
+	let (locked_ms, locked_locks) = locked?;
+	let results = List::new();
+	results.set(Value::from_string("fast_ms")?, Value::from(fast_ms))?;
+	results.set(
+		Value::from_string("fast_locks")?,
+		Value::from(fast_locks as f32),
+	)?;
+	results.set(Value::from_string("locked_ms")?, Value::from(locked_ms))?;
+	results.set(
+		Value::from_string("locked_locks")?,
+		Value::from(locked_locks as f32),
+	)?;
+	Ok(Value::from(results))
+}
+
 #[hook("/datum/gas_mixture/proc/__auxtools_parse_gas_string")]
 fn _parse_gas_string(string: Value) {
 	let actual_string = string.as_string()?;
@@ -1,16 +1,35 @@
 #[cfg(feature = "reaction_hooks")]
-mod hooks;
+pub mod hooks;
 
-use auxtools::{byond_string, runtime, shutdown, DMResult, Runtime, Value};
+use auxtools::{byond_string, hook, runtime, shutdown, DMResult, List, Proc, Runtime, Value};
 
-use crate::gas::{gas_idx_to_id, total_num_gases, GasIDX, Mixture};
+use crate::gas::{
+	constants::ReactionReturn,
+	gas_idx_to_id, mix_id,
+	mixture::{sim_floors, BitSet, SimFloors},
+	stats, total_num_gases,
+	types::with_reactions,
+	with_mix, with_mix_mut, GasIDX, Mixture,
+};
 
-use std::cell::RefCell;
+use std::{
+	cell::{Cell, RefCell},
+	sync::{
+		atomic::{AtomicU32, AtomicU64, Ordering},
+		Arc,
+	},
+};
 
 use float_ord::FloatOrd;
 
+use std::cmp::Reverse;
+
 pub type ReactionPriority = FloatOrd<f32>;
 
+/// Where a reaction goes in the order reactions run in: its priority, then the order it was registered in.
+/// Reactions run from the highest down, so reactions with the same priority run in the order they were registered.
+pub type ReactionOrder = (ReactionPriority, Reverse<usize>);
+
 pub type ReactionIdentifier = u64;
 
 #[derive(Clone)]
@@ -21,44 +40,719 @@ pub struct Reaction {
 	max_temp_req: Option<f32>,
 	min_ener_req: Option<f32>,
 	min_fire_req: Option<f32>,
+	min_pressure_req: Option<f32>,
+	enclosed_req: bool,
 	min_gas_reqs: Vec<(GasIDX, f32)>,
+	required_gases: BitSet,
+	cooldown: Option<u32>,
+}
+
+/// Light and sound a reaction gives off, per mole of gas it changes.
+/// Byond: `effect_light`, a number, and `effect_sound`, a string, at `effect_sound_volume`, a number.
+#[derive(Clone)]
+pub struct ReactionEffects {
+	light: Option<f32>,
+	sound: Option<(Arc<str>, f32)>,
+}
+
+/// The light and sound one reaction gave off, scaled by how many moles of gas it changed, for the holder to show.
+pub struct EffectHint {
+	pub id: ReactionIdentifier,
+	pub light: Option<f32>,
+	pub sound: Option<(Arc<str>, f32)>,
+}
+
+// Counts atmos ticks, for reaction cooldowns.
+static REACTION_TICK: AtomicU32 = AtomicU32::new(0);
+
+/// The current reaction tick, which reaction cooldowns are measured in.
+#[must_use]
+pub fn current_reaction_tick() -> u32 {
+	REACTION_TICK.load(Ordering::Relaxed)
+}
+
+/// Moves reaction cooldowns forward by one tick. Turf processing does this every run.
+pub fn advance_reaction_tick() -> u32 {
+	REACTION_TICK
+		.fetch_add(1, Ordering::Relaxed)
+		.wrapping_add(1)
+}
+
+// Seed for reaction RNG, see auxmos_set_reaction_seed.
+static REACTION_SEED: AtomicU64 = AtomicU64::new(0);
+
+// The splitmix64 finalizer.
+const fn mix_bits(mut x: u64) -> u64 {
+	x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+	x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+	x ^ (x >> 31)
+}
+
+/// A small splitmix64 RNG for reactions with a random yield, so they don't need DM's RNG and can run off the main thread.
+/// It's seeded from the global reaction seed, the reaction tick, the reaction and the mix, so the results are deterministic
+/// given the same seed and processing order. That also means a reaction gets the same numbers if it runs twice on a mix in one tick.
+pub struct ReactionRng(u64);
+
+impl ReactionRng {
+	#[must_use]
+	pub fn new(reaction: ReactionIdentifier, mix_id: usize) -> Self {
+		let seed = mix_bits(REACTION_SEED.load(Ordering::Relaxed) ^ reaction);
+		let seed = mix_bits(seed ^ u64::from(current_reaction_tick()));
+		Self(mix_bits(seed ^ mix_id as u64))
+	}
+	pub fn next_u64(&mut self) -> u64 {
+		self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+		mix_bits(self.0)
+	}
+	/// A number from 0 up to, but not including, 1.
+	pub fn next_f32(&mut self) -> f32 {
+		(self.next_u64() >> 40) as f32 / (1_u32 << 24) as f32
+	}
+}
+
+/// Args: (n). Sets the seed reaction RNG starts from, e.g. for reproducible tests.
+#[hook("/proc/auxmos_set_reaction_seed")]
+fn _hook_set_reaction_seed(seed: Value) {
+	let seed = seed.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	REACTION_SEED.store(u64::from(seed.to_bits()), Ordering::Relaxed);
+	Ok(Value::null())
+}
+
+/// Args: (reaction_id, count). Lets byond reactions use reaction RNG too.
+/// Returns: a list of `count` numbers from 0 to 1, the same ones for the same reaction on this mix until the next reaction tick.
+#[hook("/datum/gas_mixture/proc/reaction_rand")]
+fn _reaction_rand_hook(reaction_id: Value, count: Value) {
+	let count = count.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut rng = ReactionRng::new(
+		reaction_id_from_string(&reaction_id.as_string()?),
+		mix_id(src)?,
+	);
+	let numbers = List::new();
+	for _ in 0..count.max(0.0) as usize {
+		numbers.append(Value::from(rng.next_f32()));
+	}
+	Ok(Value::from(numbers))
+}
+
+/// What's around a mix while it reacts, for reactions whose conditions depend on more than the mix itself.
+/// The default is what reactions have always assumed: the mix's own pressure, and not exposed to space.
+#[derive(Clone, Copy, Default)]
+pub struct ReactionEnv {
+	/// The pressure around the mix, in kilopascals. If None, the mix's own pressure is used.
+	pub ambient_pressure: Option<f32>,
+	/// Whether the mix is open to space, e.g. on a turf next to space.
+	pub open_to_space: bool,
+}
+
+impl ReactionEnv {
+	/// The pressure reactions should see, given the mix reacting.
+	#[must_use]
+	pub fn pressure(&self, mix: &Mixture) -> f32 {
+		self.ambient_pressure
+			.unwrap_or_else(|| mix.return_pressure())
+	}
 }
 
 use fxhash::FxBuildHasher;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 enum ReactionSide {
 	ByondSide(Value),
-	RustSide(fn(&Value, &Value) -> DMResult<Value>),
+	RustSide(HookFunc),
 }
 
+/// Something a reaction hooked in Rust does outside of its mix. These all call into byond, so they're only done on the main thread.
+pub enum HookEffect {
+	/// Sets the mix's `reaction_results["fire"]`.
+	#[cfg_attr(
+		not(any(
+			feature = "plasma_fire_hook",
+			feature = "trit_fire_hook",
+			feature = "generic_fire_hook"
+		)),
+		allow(dead_code)
+	)]
+	FireResult(f32),
+	/// Calls `fire_expose(holder, mix, temperature)`.
+	#[cfg_attr(
+		not(any(
+			feature = "plasma_fire_hook",
+			feature = "trit_fire_hook",
+			feature = "generic_fire_hook"
+		)),
+		allow(dead_code)
+	)]
+	FireExpose(f32),
+	/// Calls `radiation_burn(holder, energy)`.
+	#[cfg_attr(
+		not(any(feature = "trit_fire_hook", feature = "generic_fire_hook")),
+		allow(dead_code)
+	)]
+	RadiationBurn(f32),
+	/// Calls the mix's `set_analyzer_results(instability)`.
+	#[cfg_attr(not(feature = "fusion_hook"), allow(dead_code))]
+	AnalyzerResults(f32),
+	/// Calls `fusion_ball(holder, reaction_energy, standard_energy)`.
+	#[cfg_attr(not(feature = "fusion_hook"), allow(dead_code))]
+	FusionBall(f32, f32),
+}
+
+/// A reaction hooked in Rust. It only changes the mix it's given and leaves everything else to `effects`,
+/// so it's safe to run on any thread under the mix's write lock. Gets the sim floors read for the whole batch it's in.
+pub type HookFunc =
+	fn(&mut Mixture, SimFloors, &mut Vec<HookEffect>) -> Result<ReactionReturn, Runtime>;
+
 thread_local! {
 	static REACTION_VALUES: RefCell<HashMap<ReactionIdentifier, ReactionSide, FxBuildHasher>> = Default::default();
+	static REACTION_STRING_IDS: RefCell<HashMap<ReactionIdentifier, Box<str>, FxBuildHasher>> = Default::default();
+	// The cooldown of every reaction that has one, in reaction ticks, so reacting doesn't have to look through every reaction for it.
+	static REACTION_COOLDOWNS: RefCell<HashMap<ReactionIdentifier, u32, FxBuildHasher>> = Default::default();
+	// The effects of every reaction that has any, so reacting with effects doesn't have to look through every reaction for them.
+	static REACTION_EFFECTS: RefCell<HashMap<ReactionIdentifier, ReactionEffects, FxBuildHasher>> = Default::default();
+	// Set while react_preview runs, so reactions don't leave anything behind outside the mix.
+	static PREVIEWING: Cell<bool> = Cell::new(false);
+}
+
+/// Whether reactions are only being previewed, see `react_preview`.
+/// Reactions shouldn't do anything outside of their mix while this is true, e.g. expose their holder to fire.
+#[must_use]
+pub fn is_previewing() -> bool {
+	PREVIEWING.with(Cell::get)
 }
 
 #[shutdown]
 fn clean_up_reaction_values() {
 	crate::turfs::wait_for_tasks();
+	REACTION_TICK.store(0, Ordering::Relaxed);
+	REACTION_SEED.store(0, Ordering::Relaxed);
 	REACTION_VALUES.with(|reaction_values| {
 		reaction_values.borrow_mut().clear();
 	});
+	REACTION_STRING_IDS.with(|string_ids| {
+		string_ids.borrow_mut().clear();
+	});
+	REACTION_COOLDOWNS.with(|cooldowns| {
+		cooldowns.borrow_mut().clear();
+	});
+	REACTION_EFFECTS.with(|effects| {
+		effects.borrow_mut().clear();
+	});
+}
+
+// Just calls the reaction, without recording anything.
+fn call_reaction(id: ReactionIdentifier, src: &Value, holder: &Value) -> DMResult {
+	REACTION_VALUES.with(|r| {
+		r.borrow().get(&id).map_or_else(
+			|| Err(runtime!("Reaction with invalid id")),
+			|reaction| match reaction {
+				ReactionSide::ByondSide(val) => val.call("react", &[src, holder]),
+				ReactionSide::RustSide(func) => {
+					let mut effects = Vec::new();
+					let result = with_mix_mut(src, |air| func(air, sim_floors(), &mut effects))?;
+					apply_hook_effects(effects, src, holder)?;
+					Ok(Value::from(result.bits() as f32))
+				}
+			},
+		)
+	})
+}
+
+// Does what hooked reactions left for the main thread, to the mix's datum and its holder.
+// Nothing's done to the holder while previewing.
+fn apply_hook_effects(
+	effects: Vec<HookEffect>,
+	src: &Value,
+	holder: &Value,
+) -> Result<(), Runtime> {
+	for effect in effects {
+		match effect {
+			HookEffect::FireResult(amount) => {
+				src.get_list(byond_string!("reaction_results"))
+					.map_err(|_| {
+						runtime!(
+							"Attempt to interpret non-list value as list {} {}:{}",
+							std::file!(),
+							std::line!(),
+							std::column!()
+						)
+					})?
+					.set(byond_string!("fire"), Value::from(amount))?;
+			}
+			HookEffect::AnalyzerResults(instability) => {
+				src.call("set_analyzer_results", &[&Value::from(instability)])?;
+			}
+			_ if is_previewing() => (),
+			HookEffect::FireExpose(temperature) => {
+				if let Some(fire_expose) = Proc::find(byond_string!("/proc/fire_expose")) {
+					fire_expose.call(&[holder, src, &Value::from(temperature)])?;
+				} else {
+					Proc::find(byond_string!("/proc/stack_trace"))
+						.ok_or_else(|| runtime!("Couldn't find stack_trace!"))?
+						.call(&[&Value::from_string(
+							"fire_expose not found! Auxmos hooked fires do not work without it!",
+						)?])?;
+				}
+			}
+			HookEffect::RadiationBurn(energy) => {
+				if let Some(radiation_burn) = Proc::find(byond_string!("/proc/radiation_burn")) {
+					radiation_burn.call(&[holder, &Value::from(energy)])?;
+				} else {
+					drop(
+						Proc::find(byond_string!("/proc/stack_trace"))
+							.ok_or_else(|| runtime!("Couldn't find stack_trace!"))?
+							.call(&[&Value::from_string(
+								"radiation_burn not found! Auxmos hooked fires won't irradiate without it!",
+							)?]),
+					);
+				}
+			}
+			HookEffect::FusionBall(reaction_energy, standard_energy) => {
+				Proc::find(byond_string!("/proc/fusion_ball"))
+					.ok_or_else(|| runtime!("Couldn't find fusion_ball!"))?
+					.call(&[
+						holder,
+						&Value::from(reaction_energy),
+						&Value::from(standard_energy),
+					])?;
+			}
+		}
+	}
+	Ok(())
+}
+
+/// The reactions hooked in Rust, and which reactions have cooldowns, copied out of the main thread's tables
+/// so reactions can be run on other threads, see `HookedReactions::react`.
+pub struct HookedReactions {
+	funcs: HashMap<ReactionIdentifier, HookFunc, FxBuildHasher>,
+	cooldowns: HashSet<ReactionIdentifier, FxBuildHasher>,
+	floors: SimFloors,
+}
+
+/// Copies out the reactions hooked in Rust, for running them off the main thread. Main thread only.
+#[must_use]
+pub fn hooked_reactions() -> HookedReactions {
+	HookedReactions {
+		funcs: REACTION_VALUES.with(|r| {
+			r.borrow()
+				.iter()
+				.filter_map(|(&id, side)| match side {
+					ReactionSide::RustSide(func) => Some((id, *func)),
+					ReactionSide::ByondSide(_) => None,
+				})
+				.collect()
+		}),
+		cooldowns: REACTION_COOLDOWNS
+			.with(|cooldowns| cooldowns.borrow().keys().copied().collect()),
+		floors: sim_floors(),
+	}
+}
+
+impl HookedReactions {
+	/// Runs the mix's reactions in order, recording them like `react_by_id`, for as long as they're hooked in Rust.
+	/// Stops at the first reaction defined in DM, since those can only run on the main thread;
+	/// it and the rest are left for `HookedReaction::finish`, along with the hooked reactions' effects.
+	/// # Errors
+	/// If a hooked reaction has a runtime.
+	pub fn react(&self, mix: &mut Mixture) -> Result<HookedReaction, Runtime> {
+		let mut reaction = HookedReaction {
+			result: ReactionReturn::NO_REACTION,
+			reacted: false,
+			effects: Vec::new(),
+			remaining: Vec::new(),
+		};
+		let reactions = mix.all_reactable();
+		for (i, &id) in reactions.iter().enumerate() {
+			let func = match self.funcs.get(&id) {
+				Some(func) => func,
+				None => {
+					reaction.remaining = reactions[i..].to_vec();
+					break;
+				}
+			};
+			let result = run_hook(
+				id,
+				*func,
+				mix,
+				self.floors,
+				&mut reaction.effects,
+				self.cooldowns.contains(&id),
+			)?;
+			reaction.reacted |= result.contains(ReactionReturn::REACTING);
+			reaction.result |= result;
+			if reaction.result.contains(ReactionReturn::STOP_REACTIONS) {
+				break;
+			}
+		}
+		Ok(reaction)
+	}
+}
+
+/// What `HookedReactions::react` did to a mix off the main thread, and what's left for the main thread to do.
+pub struct HookedReaction {
+	result: ReactionReturn,
+	reacted: bool,
+	effects: Vec<HookEffect>,
+	remaining: Vec<ReactionIdentifier>,
+}
+
+impl HookedReaction {
+	/// Finishes reacting the mix on the main thread: does what its hooked reactions did outside of it, then runs the rest of its reactions.
+	/// Returns whether any of the mix's reactions reacted.
+	/// # Errors
+	/// If any reaction has a runtime.
+	pub fn finish(self, src: &Value, holder: &Value) -> Result<bool, Runtime> {
+		apply_hook_effects(self.effects, src, holder)?;
+		let mut ret = self.result;
+		let mut reacted = self.reacted;
+		for reaction in self.remaining {
+			let result = ReactionReturn::from_bits_truncate(
+				react_by_id(reaction, src, holder)?
+					.as_number()
+					.unwrap_or_default() as u32,
+			);
+			reacted |= result.contains(ReactionReturn::REACTING);
+			ret |= result;
+			if ret.contains(ReactionReturn::STOP_REACTIONS) {
+				break;
+			}
+		}
+		Ok(reacted)
+	}
 }
 
 /// Runs a reaction given a `ReactionIdentifier`. Returns the result of the reaction, error or success.
+/// Stats, history and cooldowns aren't recorded while previewing.
 /// # Errors
 /// If the reaction itself has a runtime.
 pub fn react_by_id(id: ReactionIdentifier, src: &Value, holder: &Value) -> DMResult {
+	if is_previewing() {
+		return call_reaction(id, src, holder);
+	}
+	let has_cooldown = REACTION_COOLDOWNS.with(|cooldowns| cooldowns.borrow().contains_key(&id));
 	REACTION_VALUES.with(|r| {
 		r.borrow().get(&id).map_or_else(
 			|| Err(runtime!("Reaction with invalid id")),
 			|reaction| match reaction {
-				ReactionSide::ByondSide(val) => val.call("react", &[src, holder]),
-				ReactionSide::RustSide(func) => func(src, holder),
+				ReactionSide::ByondSide(val) => {
+					let before = stats::enabled()
+						.then(|| with_mix(src, |mix| Ok(mix.moles_copy())))
+						.transpose()?;
+					let result = val.call("react", &[src, holder])?;
+					let reacted = ReactionReturn::from_bits_truncate(
+						result.as_number().unwrap_or_default() as u32,
+					)
+					.contains(ReactionReturn::REACTING);
+					with_mix_mut(src, |after| {
+						if let Some(before) = &before {
+							stats::record_difference(before, after);
+						}
+						after.record_history();
+						if reacted && has_cooldown {
+							after.set_reacted(id, current_reaction_tick());
+						}
+						Ok(())
+					})?;
+					Ok(result)
+				}
+				ReactionSide::RustSide(func) => {
+					let mut effects = Vec::new();
+					let result = with_mix_mut(src, |air| {
+						run_hook(id, *func, air, sim_floors(), &mut effects, has_cooldown)
+					})?;
+					apply_hook_effects(effects, src, holder)?;
+					Ok(Value::from(result.bits() as f32))
+				}
 			},
 		)
 	})
 }
 
+// Runs a hooked reaction on the mix, recording its stats, history and cooldown like react_by_id does.
+fn run_hook(
+	id: ReactionIdentifier,
+	func: HookFunc,
+	mix: &mut Mixture,
+	floors: SimFloors,
+	effects: &mut Vec<HookEffect>,
+	has_cooldown: bool,
+) -> Result<ReactionReturn, Runtime> {
+	let before = stats::enabled().then(|| mix.moles_copy());
+	let result = func(mix, floors, effects)?;
+	if let Some(before) = before {
+		stats::record_difference(&before, mix);
+	}
+	mix.record_history();
+	if has_cooldown && result.contains(ReactionReturn::REACTING) {
+		mix.set_reacted(id, current_reaction_tick());
+	}
+	Ok(result)
+}
+
+/// Returns: an associative list of reaction IDs to their priorities, in the order reactions run:
+/// highest priority first, with reactions of the same priority in the order they were registered.
+#[hook("/proc/auxmos_reaction_priorities")]
+fn _hook_reaction_priorities() {
+	let reactions = with_reactions(|reactions| {
+		reactions
+			.values()
+			.rev()
+			.map(|reaction| (reaction.id, reaction.get_priority().0))
+			.collect::<Vec<_>>()
+	});
+	let priorities = List::new();
+	for (id, priority) in reactions {
+		priorities.set(reaction_string_id(id)?, Value::from(priority))?;
+	}
+	Ok(Value::from(priorities))
+}
+
+/// Moves reaction cooldowns forward by a tick. Only needed if turf processing isn't doing it already.
+/// Returns: the new reaction tick.
+#[hook("/proc/auxmos_advance_reaction_tick")]
+fn _hook_advance_reaction_tick() {
+	Ok(Value::from(advance_reaction_tick() as f32))
+}
+
+/// Runs every reaction the mix can currently do, stopping early if a reaction asks to.
+/// Returns the combined result flags and the identifiers of every reaction that actually reacted.
+/// # Errors
+/// If any reaction has a runtime.
+pub fn react_with_report(
+	src: &Value,
+	holder: &Value,
+) -> Result<(ReactionReturn, Vec<ReactionIdentifier>), Runtime> {
+	let mut ret = ReactionReturn::NO_REACTION;
+	let mut fired = Vec::new();
+	let reactions = with_mix(src, |mix| Ok(mix.all_reactable()))?;
+	for reaction in reactions {
+		let result = ReactionReturn::from_bits_truncate(
+			react_by_id(reaction, src, holder)?
+				.as_number()
+				.unwrap_or_default() as u32,
+		);
+		if result.contains(ReactionReturn::REACTING) {
+			fired.push(reaction);
+		}
+		ret |= result;
+		if ret.contains(ReactionReturn::STOP_REACTIONS) {
+			break;
+		}
+	}
+	Ok((ret, fired))
+}
+
+/// As `react_with_report`, but also returns the effect hints of every reaction that reacted and has effects.
+/// Reactions without effects don't add anything, or take any more time than usual.
+/// # Errors
+/// If any reaction has a runtime.
+pub fn react_with_effects(
+	src: &Value,
+	holder: &Value,
+) -> Result<(ReactionReturn, Vec<EffectHint>), Runtime> {
+	let mut ret = ReactionReturn::NO_REACTION;
+	let mut hints = Vec::new();
+	let reactions = with_mix(src, |mix| Ok(mix.all_reactable()))?;
+	for reaction in reactions {
+		let effects = REACTION_EFFECTS.with(|effects| effects.borrow().get(&reaction).cloned());
+		let before = match effects {
+			Some(_) => Some(with_mix(src, |mix| Ok(mix.snapshot()))?),
+			None => None,
+		};
+		let result = ReactionReturn::from_bits_truncate(
+			react_by_id(reaction, src, holder)?
+				.as_number()
+				.unwrap_or_default() as u32,
+		);
+		if let (Some(effects), Some(before), true) =
+			(effects, before, result.contains(ReactionReturn::REACTING))
+		{
+			let magnitude = with_mix(src, |after| {
+				Ok((0..total_num_gases())
+					.map(|idx| (after.get_moles(idx) - before.get_moles(idx)).abs())
+					.sum::<f32>())
+			})?;
+			hints.push(EffectHint {
+				id: reaction,
+				light: effects.light.map(|light| light * magnitude),
+				sound: effects
+					.sound
+					.map(|(sound, volume)| (sound, (volume * magnitude).min(100.0))),
+			});
+		}
+		ret |= result;
+		if ret.contains(ReactionReturn::STOP_REACTIONS) {
+			break;
+		}
+	}
+	Ok((ret, hints))
+}
+
+/// What one reaction did to a mix: how much of each gas it made (negative if it used it up), and how much it changed the temperature by.
+pub struct ReactionReport {
+	pub id: ReactionIdentifier,
+	pub gas_deltas: Vec<(GasIDX, f32)>,
+	pub temperature_delta: f32,
+}
+
+/// Runs every reaction the mix can currently do, like `react_with_report`, and reports what each one did,
+/// then puts the mix back exactly how it was. Nothing is recorded in stats, history or cooldowns,
+/// and auxmos's hooked reactions don't touch the holder. Reactions defined in DM are still called on the mix itself,
+/// so they should check `auxmos_is_previewing` before doing anything outside of it.
+/// # Errors
+/// If any reaction has a runtime. The mix is put back even then.
+pub fn react_preview(src: &Value, holder: &Value) -> Result<Vec<ReactionReport>, Runtime> {
+	let original = with_mix(src, |mix| Ok(mix.clone()))?;
+	PREVIEWING.with(|previewing| previewing.set(true));
+	let result = (|| {
+		let mut reports = Vec::new();
+		let min_moles = sim_floors().min_moles;
+		let reactions = with_mix(src, |mix| Ok(mix.all_reactable()))?;
+		for reaction in reactions {
+			let before = with_mix(src, |mix| Ok(mix.clone()))?;
+			let result = ReactionReturn::from_bits_truncate(
+				react_by_id(reaction, src, holder)?
+					.as_number()
+					.unwrap_or_default() as u32,
+			);
+			with_mix(src, |after| {
+				reports.push(ReactionReport {
+					id: reaction,
+					gas_deltas: (0..total_num_gases())
+						.map(|idx| (idx, after.get_moles(idx) - before.get_moles(idx)))
+						.filter(|&(_, delta)| delta.abs() > min_moles)
+						.collect(),
+					temperature_delta: after.get_temperature() - before.get_temperature(),
+				});
+				Ok(())
+			})?;
+			if result.contains(ReactionReturn::STOP_REACTIONS) {
+				break;
+			}
+		}
+		Ok(reports)
+	})();
+	PREVIEWING.with(|previewing| previewing.set(false));
+	let mut original = Some(original);
+	with_mix_mut(src, |mix| {
+		if let Some(original) = original.take() {
+			*mix = original;
+		}
+		Ok(())
+	})?;
+	result
+}
+
+/// Returns: whether reactions are only being previewed by react_preview right now.
+/// DM reactions should skip anything they'd do outside of the mix, like exposing turfs to fire, when this is true.
+#[hook("/proc/auxmos_is_previewing")]
+fn _hook_is_previewing() {
+	Ok(Value::from(is_previewing()))
+}
+
+/// Like `/datum/gas_mixture/proc/react`, but reaction conditions are checked against the given environment.
+/// Returns the combined result flags.
+/// # Errors
+/// If any reaction has a runtime.
+pub fn react_in_context(
+	src: &Value,
+	holder: &Value,
+	env: &ReactionEnv,
+) -> Result<ReactionReturn, Runtime> {
+	let mut ret = ReactionReturn::NO_REACTION;
+	let reactions = with_mix(src, |mix| Ok(mix.all_reactable_in_context(env)))?;
+	for reaction in reactions {
+		ret |= ReactionReturn::from_bits_truncate(
+			react_by_id(reaction, src, holder)?
+				.as_number()
+				.unwrap_or_default() as u32,
+		);
+		if ret.contains(ReactionReturn::STOP_REACTIONS) {
+			break;
+		}
+	}
+	Ok(ret)
+}
+
+/// Like `/datum/gas_mixture/proc/react`, but only the reactions in `allowed` can fire.
+/// An empty set allows every reaction, same as a normal react, rather than none.
+/// Returns the combined result flags.
+/// # Errors
+/// If any reaction has a runtime.
+pub fn react_allowed(
+	src: &Value,
+	holder: &Value,
+	allowed: &HashSet<ReactionIdentifier>,
+) -> Result<ReactionReturn, Runtime> {
+	let mut ret = ReactionReturn::NO_REACTION;
+	let reactions = with_mix(src, |mix| Ok(mix.all_reactable()))?;
+	for reaction in reactions {
+		if !allowed.is_empty() && !allowed.contains(&reaction) {
+			continue;
+		}
+		ret |= ReactionReturn::from_bits_truncate(
+			react_by_id(reaction, src, holder)?
+				.as_number()
+				.unwrap_or_default() as u32,
+		);
+		if ret.contains(ReactionReturn::STOP_REACTIONS) {
+			break;
+		}
+	}
+	Ok(ret)
+}
+
+/// Runs reactions on the mix over and over until a pass where nothing reacts, a reaction asks to stop, or `max_iters` passes have run.
+/// Returns the amount of passes run.
+/// Reactions lock the mix themselves, so the mix is not held locked between passes.
+/// Oscillating reactions will just run until the cap, so the result isn't necessarily stable.
+/// # Errors
+/// If any reaction has a runtime.
+pub fn react_until_stable(src: &Value, holder: &Value, max_iters: usize) -> Result<usize, Runtime> {
+	let mut iters = 0;
+	while iters < max_iters {
+		iters += 1;
+		let (result, fired) = react_with_report(src, holder)?;
+		if fired.is_empty() || result.contains(ReactionReturn::STOP_REACTIONS) {
+			break;
+		}
+	}
+	Ok(iters)
+}
+
+/// The identifier of the reaction with the given byond string ID, whether or not it's loaded.
+#[must_use]
+pub fn reaction_id_from_string(string_id: &str) -> ReactionIdentifier {
+	fxhash::hash64(string_id.as_bytes())
+}
+
+/// Returns the byond string ID of the reaction with the given identifier.
+/// # Errors
+/// If no reaction with that identifier is loaded.
+pub fn reaction_string_id(id: ReactionIdentifier) -> DMResult {
+	REACTION_STRING_IDS.with(|r| {
+		let string_ids = r.borrow();
+		Value::from_string(
+			string_ids
+				.get(&id)
+				.ok_or_else(|| runtime!("Reaction with invalid id"))?,
+		)
+	})
+}
+
 impl Reaction {
 	/// Takes a `/datum/gas_reaction` and makes a byond reaction out of it.
 	pub fn from_byond_reaction(reaction: &Value) -> Result<Self, Runtime> {
@@ -80,7 +774,29 @@ impl Reaction {
 				None
 			}
 		};
-		let id = fxhash::hash64(string_id.as_bytes());
+		let id = reaction_id_from_string(&string_id);
+		let cooldown = reaction
+			.get_number(byond_string!("cooldown_ticks"))
+			.ok()
+			.map(|ticks| ticks as u32)
+			.filter(|&ticks| ticks > 0);
+		let effect_light = reaction
+			.get_number(byond_string!("effect_light"))
+			.ok()
+			.filter(|&light| light > 0.0);
+		let effect_sound = reaction
+			.get_string(byond_string!("effect_sound"))
+			.ok()
+			.map(|sound| {
+				let volume = reaction
+					.get_number(byond_string!("effect_sound_volume"))
+					.unwrap_or(1.0);
+				(Arc::from(sound), volume)
+			});
+		let effects = (effect_light.is_some() || effect_sound.is_some()).then(|| ReactionEffects {
+			light: effect_light,
+			sound: effect_sound,
+		});
 		let our_reaction = {
 			if let Ok(min_reqs) = reaction.get_list(byond_string!("min_requirements")) {
 				let mut min_gas_reqs: Vec<(GasIDX, f32)> = Vec::new();
@@ -108,6 +824,15 @@ impl Reaction {
 					.get(byond_string!("FIRE_REAGENTS"))
 					.and_then(|v| v.as_number())
 					.ok();
+				let min_pressure_req = min_reqs
+					.get(byond_string!("MIN_PRESSURE"))
+					.and_then(|v| v.as_number())
+					.ok();
+				let enclosed_req = min_reqs
+					.get(byond_string!("ENCLOSED"))
+					.and_then(|v| v.as_number())
+					.map_or(false, |v| v != 0.0);
+				let required_gases = BitSet::from_indices(min_gas_reqs.iter().map(|&(i, _)| i));
 				Ok(Reaction {
 					id,
 					priority,
@@ -115,7 +840,11 @@ impl Reaction {
 					max_temp_req,
 					min_ener_req,
 					min_fire_req,
+					min_pressure_req,
+					enclosed_req,
 					min_gas_reqs,
+					required_gases,
+					cooldown,
 				})
 			} else {
 				Err(runtime!(format!(
@@ -137,6 +866,24 @@ impl Reaction {
 			};
 			Ok(())
 		})?;
+		REACTION_STRING_IDS.with(|r| {
+			r.borrow_mut()
+				.insert(our_reaction.id, string_id.into_boxed_str())
+		});
+		REACTION_COOLDOWNS.with(|r| {
+			let mut cooldowns = r.borrow_mut();
+			match our_reaction.cooldown {
+				Some(cooldown) => cooldowns.insert(our_reaction.id, cooldown),
+				None => cooldowns.remove(&our_reaction.id),
+			}
+		});
+		REACTION_EFFECTS.with(|r| {
+			let mut reaction_effects = r.borrow_mut();
+			match effects {
+				Some(effects) => reaction_effects.insert(our_reaction.id, effects),
+				None => reaction_effects.remove(&our_reaction.id),
+			}
+		});
 		Ok(our_reaction)
 	}
 	#[must_use]
@@ -145,15 +892,25 @@ impl Reaction {
 	}
 	/// Checks if the given gas mixture can react with this reaction.
 	pub fn check_conditions(&self, mix: &Mixture) -> bool {
-		self.min_temp_req
-			.map_or(true, |temp_req| mix.get_temperature() >= temp_req)
+		self.check_conditions_in(mix, &ReactionEnv::default())
+	}
+	/// Checks if the given gas mixture can react with this reaction, in the given environment.
+	/// Never true if the mix has its reactions suppressed.
+	pub fn check_conditions_in(&self, mix: &Mixture, env: &ReactionEnv) -> bool {
+		!mix.reactions_suppressed()
+			&& self.cooldown.map_or(true, |cooldown| {
+				mix.reaction_off_cooldown(self.id, cooldown, current_reaction_tick())
+			}) && !(self.enclosed_req && env.open_to_space)
+			&& self
+				.min_pressure_req
+				.map_or(true, |pressure_req| env.pressure(mix) >= pressure_req)
+			&& self
+				.min_temp_req
+				.map_or(true, |temp_req| mix.get_temperature() >= temp_req)
 			&& self
 				.max_temp_req
 				.map_or(true, |temp_req| mix.get_temperature() <= temp_req)
-			&& self
-				.min_gas_reqs
-				.iter()
-				.all(|&(k, v)| mix.get_moles(k) >= v)
+			&& mix.contains_at_least(&self.min_gas_reqs)
 			&& self
 				.min_ener_req
 				.map_or(true, |ener_req| mix.thermal_energy() >= ener_req)
@@ -162,6 +919,12 @@ impl Reaction {
 				oxi.min(fuel) >= fire_req
 			})
 	}
+	/// Whether the mix has every gas this reaction needs some of, going by the mix's presence mask.
+	/// A quick check to skip reactions with before `check_conditions`, which still checks the amounts.
+	#[must_use]
+	pub fn has_required_gases(&self, present: &BitSet) -> bool {
+		present.is_superset(&self.required_gases)
+	}
 	/// Returns the priority of the reaction.
 	#[must_use]
 	pub fn get_priority(&self) -> ReactionPriority {
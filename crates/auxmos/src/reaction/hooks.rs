@@ -1,14 +1,101 @@
 use auxtools::*;
 
 use crate::gas::{
-	constants::*, gas_fusion_power, gas_idx_from_string, with_gas_info, with_mix, with_mix_mut,
-	FireProductInfo, GasIDX,
+	constants::*, gas_fusion_power, gas_idx_from_string, mixture::SimFloors, with_gas_info,
+	FireProductInfo, GasIDX, Mixture,
 };
 
-const SUPER_SATURATION_THRESHOLD: f32 = 96.0;
+use super::{HookEffect, HookFunc};
+
+use parking_lot::{const_rwlock, RwLock};
+
+// Plasma fires burn at full rate above this temperature.
+const PLASMA_UPPER_TEMPERATURE: f32 = 1390.0 + T0C;
+
+// Fire tuning that can be changed at runtime, see auxmos_set_fire_tuning.
+#[derive(Copy, Clone)]
+struct FireTuning {
+	// Energy released per mole of plasma burned.
+	#[cfg_attr(not(feature = "plasma_fire_hook"), allow(dead_code))]
+	plasma_energy_released: f32,
+	// Hooked fires don't burn or expose their holder below this temperature.
+	#[cfg_attr(
+		not(any(
+			feature = "plasma_fire_hook",
+			feature = "trit_fire_hook",
+			feature = "generic_fire_hook"
+		)),
+		allow(dead_code)
+	)]
+	minimum_temperature: f32,
+	// Fires with more oxidizer to fuel than this make tritium instead of CO2.
+	#[cfg_attr(
+		not(any(feature = "plasma_fire_hook", feature = "generic_fire_hook")),
+		allow(dead_code)
+	)]
+	super_saturation_threshold: f32,
+}
+
+static FIRE_TUNING: RwLock<FireTuning> = const_rwlock(FireTuning {
+	plasma_energy_released: 3_000_000.0,
+	minimum_temperature: FIRE_MINIMUM_TEMPERATURE_TO_EXIST,
+	super_saturation_threshold: 96.0,
+});
+
+fn fire_tuning() -> FireTuning {
+	*FIRE_TUNING.read()
+}
+
+/// Args: (list). Takes an associative list of any of "energy_released", "minimum_temperature" and "products_ratio".
+/// Sets the energy plasma fires release per mole burned, the temperature hooked fires need to burn
+/// and the oxidizer to fuel ratio above which they make tritium instead of CO2.
+/// The minimum temperature has to be below the temperature plasma fires burn at full rate, 1390 C.
+#[hook("/proc/auxmos_set_fire_tuning")]
+fn _hook_set_fire_tuning(params: Value) {
+	let list = params.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut tuning = fire_tuning();
+	for i in 1..=list.len() {
+		let key = list.get(i)?;
+		let amt = list.get(key.clone())?.as_number().map_err(|_| {
+			runtime!(
+				"Attempt to interpret non-number value as number {} {}:{}",
+				std::file!(),
+				std::line!(),
+				std::column!()
+			)
+		})?;
+		if !amt.is_finite() || amt < 0.0 {
+			return Err(runtime!("Invalid fire tuning value: {}", amt));
+		}
+		match key.as_string()?.as_str() {
+			"energy_released" => tuning.plasma_energy_released = amt,
+			"minimum_temperature" => {
+				if amt >= PLASMA_UPPER_TEMPERATURE {
+					return Err(runtime!(
+						"Fire minimum temperature must be below {}: {}",
+						PLASMA_UPPER_TEMPERATURE,
+						amt
+					));
+				}
+				tuning.minimum_temperature = amt;
+			}
+			"products_ratio" => tuning.super_saturation_threshold = amt,
+			other => return Err(runtime!("Unknown fire tuning parameter: {}", other)),
+		}
+	}
+	*FIRE_TUNING.write() = tuning;
+	Ok(Value::null())
+}
 
 #[must_use]
-pub fn func_from_id(id: &str) -> Option<ReactFunc> {
+pub fn func_from_id(id: &str) -> Option<HookFunc> {
 	match id {
 		#[cfg(feature = "plasma_fire_hook")]
 		"plasmafire" => Some(plasma_fire),
@@ -22,173 +109,139 @@ pub fn func_from_id(id: &str) -> Option<ReactFunc> {
 	}
 }
 
-type ReactFunc = fn(&Value, &Value) -> DMResult<Value>;
-
 #[cfg(feature = "plasma_fire_hook")]
-fn plasma_fire(byond_air: &Value, holder: &Value) -> DMResult<Value> {
-	const PLASMA_UPPER_TEMPERATURE: f32 = 1390.0 + T0C;
+fn plasma_fire(
+	air: &mut Mixture,
+	floors: SimFloors,
+	effects: &mut Vec<HookEffect>,
+) -> Result<ReactionReturn, Runtime> {
 	const OXYGEN_BURN_RATE_BASE: f32 = 1.4;
 	const PLASMA_OXYGEN_FULLBURN: f32 = 10.0;
 	const PLASMA_BURN_RATE_DELTA: f32 = 9.0;
-	const FIRE_PLASMA_ENERGY_RELEASED: f32 = 3_000_000.0;
+	let tuning = fire_tuning();
 	let o2 = gas_idx_from_string(GAS_O2)?;
 	let plasma = gas_idx_from_string(GAS_PLASMA)?;
 	let co2 = gas_idx_from_string(GAS_CO2)?;
 	let tritium = gas_idx_from_string(GAS_TRITIUM)?;
-	let (oxygen_burn_rate, plasma_burn_rate, initial_oxy, initial_plasma, initial_energy) =
-		with_mix(byond_air, |air| {
-			let temperature_scale = {
-				if air.get_temperature() > PLASMA_UPPER_TEMPERATURE {
-					1.0
+	let (oxygen_burn_rate, plasma_burn_rate, initial_oxy, initial_plasma, initial_energy) = {
+		let temperature_scale = {
+			if air.get_temperature() > PLASMA_UPPER_TEMPERATURE {
+				1.0
+			} else {
+				(air.get_temperature() - tuning.minimum_temperature)
+					/ (PLASMA_UPPER_TEMPERATURE - tuning.minimum_temperature)
+			}
+		};
+		if temperature_scale > 0.0 {
+			let oxygen_burn_rate = OXYGEN_BURN_RATE_BASE - temperature_scale;
+			let oxy = air.get_moles(o2);
+			let plas = air.get_moles(plasma);
+			let plasma_burn_rate = {
+				if oxy > plas * PLASMA_OXYGEN_FULLBURN {
+					plas * temperature_scale / PLASMA_BURN_RATE_DELTA
 				} else {
-					(air.get_temperature() - FIRE_MINIMUM_TEMPERATURE_TO_EXIST)
-						/ (PLASMA_UPPER_TEMPERATURE - FIRE_MINIMUM_TEMPERATURE_TO_EXIST)
+					(temperature_scale * (oxy / PLASMA_OXYGEN_FULLBURN)) / PLASMA_BURN_RATE_DELTA
 				}
-			};
-			if temperature_scale > 0.0 {
-				let oxygen_burn_rate = OXYGEN_BURN_RATE_BASE - temperature_scale;
-				let oxy = air.get_moles(o2);
-				let plas = air.get_moles(plasma);
-				let plasma_burn_rate = {
-					if oxy > plas * PLASMA_OXYGEN_FULLBURN {
-						plas * temperature_scale / PLASMA_BURN_RATE_DELTA
-					} else {
-						(temperature_scale * (oxy / PLASMA_OXYGEN_FULLBURN))
-							/ PLASMA_BURN_RATE_DELTA
-					}
-				}
-				.min(plas)
-				.min(oxy / oxygen_burn_rate);
-				Ok((
-					oxygen_burn_rate,
-					plasma_burn_rate,
-					oxy,
-					plas,
-					air.thermal_energy(),
-				))
-			} else {
-				Ok((0.0, -1.0, 0.0, 0.0, 0.0))
 			}
-		})?;
+			.min(plas)
+			.min(oxy / oxygen_burn_rate);
+			(
+				oxygen_burn_rate,
+				plasma_burn_rate,
+				oxy,
+				plas,
+				air.thermal_energy(),
+			)
+		} else {
+			(0.0, -1.0, 0.0, 0.0, 0.0)
+		}
+	};
 	let fire_amount = plasma_burn_rate * (1.0 + oxygen_burn_rate);
 	if fire_amount > 0.0 {
-		let temperature = with_mix_mut(byond_air, |air| {
-			air.set_moles(plasma, initial_plasma - plasma_burn_rate);
-			air.set_moles(o2, initial_oxy - (plasma_burn_rate * oxygen_burn_rate));
-			if initial_oxy / initial_plasma > SUPER_SATURATION_THRESHOLD {
-				air.adjust_moles(tritium, plasma_burn_rate);
-			} else {
-				air.adjust_moles(co2, plasma_burn_rate);
-			}
-			let new_temp = (initial_energy + plasma_burn_rate * FIRE_PLASMA_ENERGY_RELEASED)
-				/ air.heat_capacity();
-			air.set_temperature(new_temp);
-			air.garbage_collect();
-			Ok(new_temp)
-		})?;
-		let cached_results = byond_air
-			.get_list(byond_string!("reaction_results"))
-			.map_err(|_| {
-				runtime!(
-					"Attempt to interpret non-list value as list {} {}:{}",
-					std::file!(),
-					std::line!(),
-					std::column!()
-				)
-			})?;
-		cached_results.set(byond_string!("fire"), Value::from(fire_amount))?;
-		if temperature > FIRE_MINIMUM_TEMPERATURE_TO_EXIST {
-			if let Some(fire_expose) = Proc::find(byond_string!("/proc/fire_expose")) {
-				fire_expose.call(&[holder, byond_air, &Value::from(temperature)])?;
-			} else {
-				Proc::find(byond_string!("/proc/stack_trace"))
-					.ok_or_else(|| runtime!("Couldn't find stack_trace!"))?
-					.call(&[&Value::from_string(
-						"fire_expose not found! Auxmos hooked fires do not work without it!",
-					)?])?;
-			}
+		air.set_moles_with_floors(plasma, initial_plasma - plasma_burn_rate, floors);
+		air.record_fuel_burnt(plasma_burn_rate);
+		air.set_moles_with_floors(
+			o2,
+			initial_oxy - (plasma_burn_rate * oxygen_burn_rate),
+			floors,
+		);
+		if initial_oxy / initial_plasma > tuning.super_saturation_threshold {
+			air.adjust_moles_with_floors(tritium, plasma_burn_rate, floors);
+		} else {
+			air.adjust_moles_with_floors(co2, plasma_burn_rate, floors);
+		}
+		let temperature = (initial_energy + plasma_burn_rate * tuning.plasma_energy_released)
+			/ air.heat_capacity();
+		air.set_temperature_with_floors(temperature, floors);
+		air.garbage_collect_with_floors(floors);
+		effects.push(HookEffect::FireResult(fire_amount));
+		if temperature > tuning.minimum_temperature {
+			effects.push(HookEffect::FireExpose(temperature));
 		}
-		Ok(Value::from(1.0))
+		Ok(ReactionReturn::REACTING)
 	} else {
-		Ok(Value::from(0.0))
+		Ok(ReactionReturn::NO_REACTION)
 	}
 }
 
 #[cfg(feature = "trit_fire_hook")]
-fn tritium_fire(byond_air: &Value, holder: &Value) -> DMResult<Value> {
+fn tritium_fire(
+	air: &mut Mixture,
+	floors: SimFloors,
+	effects: &mut Vec<HookEffect>,
+) -> Result<ReactionReturn, Runtime> {
 	const TRITIUM_BURN_OXY_FACTOR: f32 = 100.0;
 	const TRITIUM_BURN_TRIT_FACTOR: f32 = 10.0;
 	const TRITIUM_MINIMUM_RADIATION_FACTOR: f32 = 0.1;
 	const FIRE_HYDROGEN_ENERGY_RELEASED: f32 = 280_000.0;
+	let tuning = fire_tuning();
 	let o2 = gas_idx_from_string(GAS_O2)?;
 	let tritium = gas_idx_from_string(GAS_TRITIUM)?;
 	let water = gas_idx_from_string(GAS_H2O)?;
-	let (burned_fuel, energy_released, temperature) = with_mix_mut(byond_air, |air| {
-		let initial_oxy = air.get_moles(o2);
-		let initial_trit = air.get_moles(tritium);
-		let initial_energy = air.thermal_energy();
-		let burned_fuel = {
-			if initial_oxy < initial_trit {
-				let r = initial_oxy / TRITIUM_BURN_OXY_FACTOR;
-				air.set_moles(tritium, initial_trit - r);
-				r
-			} else {
-				// yes, we set burned_fuel to trit times ten. times ten!! and then the actual amount burned is 1% of that.
-				// this is why trit bombs are Like That.
-				let r = initial_trit * TRITIUM_BURN_TRIT_FACTOR;
-				air.set_moles(
-					tritium,
-					initial_trit - initial_trit / TRITIUM_BURN_TRIT_FACTOR,
-				);
-				air.set_moles(o2, initial_oxy - initial_trit);
-				r
-			}
-		};
-		air.adjust_moles(water, burned_fuel / TRITIUM_BURN_OXY_FACTOR);
-		let energy_released = FIRE_HYDROGEN_ENERGY_RELEASED * burned_fuel;
-		let new_temp = (initial_energy + energy_released) / air.heat_capacity();
-		let cached_results = byond_air
-			.get_list(byond_string!("reaction_results"))
-			.map_err(|_| {
-				runtime!(
-					"Attempt to interpret non-list value as list {} {}:{}",
-					std::file!(),
-					std::line!(),
-					std::column!()
-				)
-			})?;
-		cached_results.set(byond_string!("fire"), Value::from(burned_fuel))?;
-		air.set_temperature(new_temp);
-		air.garbage_collect();
-		Ok((burned_fuel, energy_released, new_temp))
-	})?;
-	if burned_fuel > TRITIUM_MINIMUM_RADIATION_FACTOR {
-		if let Some(radiation_burn) = Proc::find(byond_string!("/proc/radiation_burn")) {
-			radiation_burn.call(&[holder, &Value::from(energy_released)])?;
+	let initial_oxy = air.get_moles(o2);
+	let initial_trit = air.get_moles(tritium);
+	let initial_energy = air.thermal_energy();
+	let burned_fuel = {
+		if initial_oxy < initial_trit {
+			let r = initial_oxy / TRITIUM_BURN_OXY_FACTOR;
+			air.set_moles_with_floors(tritium, initial_trit - r, floors);
+			air.record_fuel_burnt(r);
+			r
 		} else {
-			drop(Proc::find(byond_string!("/proc/stack_trace"))
-			.ok_or_else(|| runtime!("Couldn't find stack_trace!"))?
-			.call(&[&Value::from_string(
-				"radiation_burn not found! Auxmos hooked trit fires won't irradiated without it!"
-			)?]));
+			// yes, we set burned_fuel to trit times ten. times ten!! and then the actual amount burned is 1% of that.
+			// this is why trit bombs are Like That.
+			let r = initial_trit * TRITIUM_BURN_TRIT_FACTOR;
+			air.set_moles_with_floors(
+				tritium,
+				initial_trit - initial_trit / TRITIUM_BURN_TRIT_FACTOR,
+				floors,
+			);
+			air.set_moles_with_floors(o2, initial_oxy - initial_trit, floors);
+			air.record_fuel_burnt(initial_trit / TRITIUM_BURN_TRIT_FACTOR);
+			r
 		}
+	};
+	air.adjust_moles_with_floors(water, burned_fuel / TRITIUM_BURN_OXY_FACTOR, floors);
+	let energy_released = FIRE_HYDROGEN_ENERGY_RELEASED * burned_fuel;
+	let temperature = (initial_energy + energy_released) / air.heat_capacity();
+	effects.push(HookEffect::FireResult(burned_fuel));
+	air.set_temperature_with_floors(temperature, floors);
+	air.garbage_collect_with_floors(floors);
+	if burned_fuel > TRITIUM_MINIMUM_RADIATION_FACTOR {
+		effects.push(HookEffect::RadiationBurn(energy_released));
 	}
-	if temperature > FIRE_MINIMUM_TEMPERATURE_TO_EXIST {
-		if let Some(fire_expose) = Proc::find(byond_string!("/proc/fire_expose")) {
-			fire_expose.call(&[holder, byond_air, &Value::from(temperature)])?;
-		} else {
-			Proc::find(byond_string!("/proc/stack_trace"))
-				.ok_or_else(|| runtime!("Couldn't find stack_trace!"))?
-				.call(&[&Value::from_string(
-					"fire_expose not found! Auxmos hooked fires do not work without it!",
-				)?])?;
-		}
+	if temperature > tuning.minimum_temperature {
+		effects.push(HookEffect::FireExpose(temperature));
 	}
-	Ok(Value::from(1.0))
+	Ok(ReactionReturn::REACTING)
 }
 
 #[cfg(feature = "fusion_hook")]
-fn fusion(byond_air: &Value, holder: &Value) -> DMResult<Value> {
+fn fusion(
+	air: &mut Mixture,
+	floors: SimFloors,
+	effects: &mut Vec<HookEffect>,
+) -> Result<ReactionReturn, Runtime> {
 	const TOROID_CALCULATED_THRESHOLD: f32 = 5.96; // changing it by 0.1 generally doubles or halves fusion temps
 	const INSTABILITY_GAS_POWER_FACTOR: f32 = 3.0;
 	const PLASMA_BINDING_ENERGY: f32 = 20_000_000.0;
@@ -217,17 +270,15 @@ fn fusion(byond_air: &Value, holder: &Value) -> DMResult<Value> {
 		scale_factor,
 		temperature_scale,
 		gas_power,
-	) = with_mix(byond_air, |air| {
-		Ok((
-			air.thermal_energy(),
-			air.get_moles(plas),
-			air.get_moles(co2),
-			(air.volume / FUSION_SCALE_DIVISOR).max(FUSION_MINIMAL_SCALE),
-			air.get_temperature().log10(),
-			air.enumerate()
-				.fold(0.0, |acc, (i, amt)| acc + gas_fusion_power(&i) * amt),
-		))
-	})?;
+	) = (
+		air.thermal_energy(),
+		air.get_moles(plas),
+		air.get_moles(co2),
+		(air.volume / FUSION_SCALE_DIVISOR).max(FUSION_MINIMAL_SCALE),
+		air.get_temperature().log10(),
+		air.enumerate()
+			.fold(0.0, |acc, (i, amt)| acc + gas_fusion_power(&i) * amt),
+	);
 	//The size of the phase space hypertorus
 	let toroidal_size = TOROID_CALCULATED_THRESHOLD + {
 		if temperature_scale <= FUSION_BASE_TEMPSCALE {
@@ -237,7 +288,7 @@ fn fusion(byond_air: &Value, holder: &Value) -> DMResult<Value> {
 		}
 	};
 	let instability = (gas_power * INSTABILITY_GAS_POWER_FACTOR).rem_euclid(toroidal_size);
-	byond_air.call("set_analyzer_results", &[&Value::from(instability)])?;
+	effects.push(HookEffect::AnalyzerResults(instability));
 	let mut thermal_energy = initial_energy;
 
 	//We have to scale the amounts of carbon and plasma down a significant amount in order to show the chaotic dynamics we want
@@ -291,52 +342,62 @@ fn fusion(byond_air: &Value, holder: &Value) -> DMResult<Value> {
 	let standard_waste_gas_output =
 		scale_factor * (FUSION_TRITIUM_CONVERSION_COEFFICIENT * FUSION_TRITIUM_MOLES_USED);
 
-	let standard_energy = with_mix_mut(byond_air, |air| {
-		air.set_moles(plas, plasma);
-		air.set_moles(co2, carbon);
-
-		//The reason why you should set up a tritium production line.
-		air.adjust_moles(trit, -FUSION_TRITIUM_MOLES_USED);
+	//The reason why you should set up a tritium production line.
+	//The whole reaction scales with however much tritium was actually there, in case something else used it up this tick.
+	let waste = if delta_plasma > 0.0 { h2o } else { bz };
+	let extent = air.react_limited(
+		&[
+			(trit, -FUSION_TRITIUM_MOLES_USED),
+			(waste, standard_waste_gas_output),
+			(o2, standard_waste_gas_output), //Oxygen is a bit touchy subject
+		],
+		floors,
+	);
+	air.set_moles_with_floors(
+		plas,
+		(plasma - initial_plasma).mul_add(extent, initial_plasma),
+		floors,
+	);
+	air.set_moles_with_floors(
+		co2,
+		(carbon - initial_carbon).mul_add(extent, initial_carbon),
+		floors,
+	);
+	let thermal_energy = (thermal_energy - initial_energy).mul_add(extent, initial_energy);
+	let reaction_energy = reaction_energy * extent;
 
-		//Adds waste products
-		if delta_plasma > 0.0 {
-			air.adjust_moles(h2o, standard_waste_gas_output);
-		} else {
-			air.adjust_moles(bz, standard_waste_gas_output);
-		}
-		air.adjust_moles(o2, standard_waste_gas_output); //Oxygen is a bit touchy subject
+	let new_heat_cap = air.heat_capacity();
+	let standard_energy = 400_f32 * air.get_moles(plas) * air.get_temperature(); //Prevents putting meaningless waste gases to achieve high rads.
 
-		let new_heat_cap = air.heat_capacity();
-		let standard_energy = 400_f32 * air.get_moles(plas) * air.get_temperature(); //Prevents putting meaningless waste gases to achieve high rads.
+	//Change the temperature
+	if new_heat_cap > MINIMUM_HEAT_CAPACITY
+		&& (reaction_energy != 0.0 || instability <= FUSION_INSTABILITY_ENDOTHERMALITY)
+	{
+		air.set_temperature_with_floors(
+			(thermal_energy / new_heat_cap).clamp(floors.min_temperature, INFINITY),
+			floors,
+		);
+	}
 
-		//Change the temperature
-		if new_heat_cap > MINIMUM_HEAT_CAPACITY
-			&& (reaction_energy != 0.0 || instability <= FUSION_INSTABILITY_ENDOTHERMALITY)
-		{
-			air.set_temperature((thermal_energy / new_heat_cap).clamp(TCMB, INFINITY));
-		}
+	air.garbage_collect_with_floors(floors);
 
-		air.garbage_collect();
-		Ok(standard_energy)
-	})?;
 	if reaction_energy != 0.0 {
-		Proc::find(byond_string!("/proc/fusion_ball"))
-			.unwrap()
-			.call(&[
-				holder,
-				&Value::from(reaction_energy),
-				&Value::from(standard_energy),
-			])?;
-		Ok(Value::from(1.0))
+		effects.push(HookEffect::FusionBall(reaction_energy, standard_energy));
+		Ok(ReactionReturn::REACTING)
 	} else if reaction_energy == 0.0 && instability <= FUSION_INSTABILITY_ENDOTHERMALITY {
-		Ok(Value::from(1.0))
+		Ok(ReactionReturn::REACTING)
 	} else {
-		Ok(Value::from(0.0))
+		Ok(ReactionReturn::NO_REACTION)
 	}
 }
 
 #[cfg(feature = "generic_fire_hook")]
-fn generic_fire(byond_air: &Value, holder: &Value) -> DMResult<Value> {
+fn generic_fire(
+	air: &mut Mixture,
+	floors: SimFloors,
+	effects: &mut Vec<HookEffect>,
+) -> Result<ReactionReturn, Runtime> {
+	let tuning = fire_tuning();
 	use fxhash::FxBuildHasher;
 	use std::collections::HashMap;
 	let mut burn_results: HashMap<GasIDX, f32, FxBuildHasher> = HashMap::with_capacity_and_hasher(
@@ -344,8 +405,9 @@ fn generic_fire(byond_air: &Value, holder: &Value) -> DMResult<Value> {
 		FxBuildHasher::default(),
 	);
 	let mut radiation_released = 0.0;
+	let mut fuel_burnt = 0.0;
 	with_gas_info(|gas_info| {
-		if let Some(fire_amount) = with_mix(byond_air, |air| {
+		let fire_amount = {
 			let (mut fuels, mut oxidizers) = air.get_fire_info_with_lock(gas_info);
 			let oxidation_power = oxidizers
 				.iter()
@@ -355,115 +417,96 @@ fn generic_fire(byond_air: &Value, holder: &Value) -> DMResult<Value> {
 				.iter()
 				.copied()
 				.fold(0.0, |acc, (_, _, power)| acc + power);
-			if oxidation_power < GAS_MIN_MOLES {
-				Err(runtime!(
+			if oxidation_power < floors.min_moles {
+				return Err(runtime!(
 					"Gas has no oxidizer even though it passed oxidizer check!"
-				))
-			} else if total_fuel <= GAS_MIN_MOLES {
-				Err(runtime!(
+				));
+			}
+			if total_fuel <= floors.min_moles {
+				return Err(runtime!(
 					"Gas has no fuel even though it passed fuel check!"
-				))
+				));
+			}
+			let oxidation_ratio = oxidation_power / total_fuel;
+			if oxidation_ratio > 1.0 {
+				for (_, amt, power) in &mut oxidizers {
+					*amt /= oxidation_ratio;
+					*power /= oxidation_ratio;
+				}
 			} else {
-				let oxidation_ratio = oxidation_power / total_fuel;
-				if oxidation_ratio > 1.0 {
-					for (_, amt, power) in &mut oxidizers {
-						*amt /= oxidation_ratio;
-						*power /= oxidation_ratio;
-					}
-				} else {
-					for (_, amt, power) in &mut fuels {
-						*amt *= oxidation_ratio;
-						*power *= oxidation_ratio;
-					}
+				for (_, amt, power) in &mut fuels {
+					*amt *= oxidation_ratio;
+					*power *= oxidation_ratio;
 				}
-				for (i, a, _) in oxidizers.iter().copied().chain(fuels.iter().copied()) {
-					let amt = FIRE_MAXIMUM_BURN_RATE * a;
-					let this_gas_info = &gas_info[i as usize];
-					radiation_released += amt * this_gas_info.fire_radiation_released;
-					if let Some(product_info) = this_gas_info.fire_products.as_ref() {
-						match product_info {
-							FireProductInfo::Generic(products) => {
-								for (product_idx, product_amt) in products.iter() {
-									burn_results
-										.entry(product_idx.get()?)
-										.and_modify(|r| *r += product_amt * amt)
-										.or_insert_with(|| product_amt * amt);
-								}
-							}
-							FireProductInfo::Plasma => {
-								let product = if oxidation_ratio > SUPER_SATURATION_THRESHOLD {
-									GAS_TRITIUM
-								} else {
-									GAS_CO2
-								};
+			}
+			fuel_burnt = fuels
+				.iter()
+				.fold(0.0, |acc, &(_, a, _)| acc + FIRE_MAXIMUM_BURN_RATE * a);
+			for (i, a, _) in oxidizers.iter().copied().chain(fuels.iter().copied()) {
+				let amt = FIRE_MAXIMUM_BURN_RATE * a;
+				let this_gas_info = &gas_info[i as usize];
+				radiation_released += amt * this_gas_info.fire_radiation_released;
+				if let Some(product_info) = this_gas_info.fire_products.as_ref() {
+					match product_info {
+						FireProductInfo::Generic(products) => {
+							for (product_idx, product_amt) in products.iter() {
 								burn_results
-									.entry(gas_idx_from_string(product)?)
-									.and_modify(|r| *r += amt)
-									.or_insert_with(|| amt);
+									.entry(product_idx.get()?)
+									.and_modify(|r| *r += product_amt * amt)
+									.or_insert_with(|| product_amt * amt);
 							}
 						}
+						FireProductInfo::Plasma => {
+							let product = if oxidation_ratio > tuning.super_saturation_threshold {
+								GAS_TRITIUM
+							} else {
+								GAS_CO2
+							};
+							burn_results
+								.entry(gas_idx_from_string(product)?)
+								.and_modify(|r| *r += amt)
+								.or_insert_with(|| amt);
+						}
 					}
-					burn_results
-						.entry(i)
-						.and_modify(|r| *r -= amt)
-						.or_insert(-amt);
-				}
-				Ok(Some(
-					oxidation_power.min(total_fuel) * 2.0 * FIRE_MAXIMUM_BURN_RATE,
-				))
-			}
-		})? {
-			let temperature = with_mix_mut(byond_air, |air| {
-				// internal energy + PV, which happens to be reducible to this
-				let initial_enthalpy = air.get_temperature()
-					* (air.heat_capacity() + R_IDEAL_GAS_EQUATION * air.total_moles());
-				let mut delta_enthalpy = 0.0;
-				for (&i, &amt) in &burn_results {
-					air.adjust_moles(i, amt);
-					delta_enthalpy -= amt * gas_info[i as usize].enthalpy;
-				}
-				air.set_temperature(
-					(initial_enthalpy + delta_enthalpy)
-						/ (air.heat_capacity() + R_IDEAL_GAS_EQUATION * air.total_moles()),
-				);
-				Ok(air.get_temperature())
-			})?;
-			let cached_results = byond_air
-				.get_list(byond_string!("reaction_results"))
-				.map_err(|_| {
-					runtime!(
-						"Attempt to interpret non-list value as list {} {}:{}",
-						std::file!(),
-						std::line!(),
-						std::column!()
-					)
-				})?;
-			cached_results.set(byond_string!("fire"), Value::from(fire_amount))?;
-			if temperature > FIRE_MINIMUM_TEMPERATURE_TO_EXIST {
-				if let Some(fire_expose) = Proc::find(byond_string!("/proc/fire_expose")) {
-					fire_expose.call(&[holder, byond_air, &Value::from(temperature)])?;
-				} else {
-					Proc::find(byond_string!("/proc/stack_trace"))
-						.ok_or_else(|| runtime!("Couldn't find stack_trace!"))?
-						.call(&[&Value::from_string(
-							"fire_expose not found! Auxmos hooked fires do not work without it!",
-						)?])?;
 				}
+				burn_results
+					.entry(i)
+					.and_modify(|r| *r -= amt)
+					.or_insert(-amt);
 			}
-			if radiation_released > 0.0 {
-				if let Some(radiation_burn) = Proc::find(byond_string!("/proc/radiation_burn")) {
-					radiation_burn.call(&[holder, &Value::from(radiation_released)])?;
-				} else {
-					drop(Proc::find(byond_string!("/proc/stack_trace"))
-					.ok_or_else(|| runtime!("Couldn't find stack_trace!"))?
-					.call(&[&Value::from_string(
-						"radiation_burn not found! Auxmos hooked fires won't irradiate without it!"
-					)?]));
-				}
-			}
-			Ok(Value::from(if fire_amount > 0.0 { 1.0 } else { 0.0 }))
-		} else {
-			Ok(Value::from(0.0))
+			oxidation_power.min(total_fuel) * 2.0 * FIRE_MAXIMUM_BURN_RATE
+		};
+		let changes: Vec<(GasIDX, f32)> = burn_results.drain().collect();
+		let (temperature, extent) = {
+			// internal energy + PV, which happens to be reducible to this
+			let initial_enthalpy = air.get_temperature()
+				* (air.heat_capacity() + R_IDEAL_GAS_EQUATION * air.total_moles());
+			// a gas that's both fuel and product can run short, so the whole burn scales down with it
+			let extent = air.react_limited(&changes, floors);
+			air.record_fuel_burnt(fuel_burnt * extent);
+			let delta_enthalpy = changes.iter().fold(0.0, |acc, &(i, amt)| {
+				acc - amt * extent * gas_info[i as usize].enthalpy
+			});
+			air.set_temperature_with_floors(
+				(initial_enthalpy + delta_enthalpy)
+					/ (air.heat_capacity() + R_IDEAL_GAS_EQUATION * air.total_moles()),
+				floors,
+			);
+			(air.get_temperature(), extent)
+		};
+		let fire_amount = fire_amount * extent;
+		let radiation_released = radiation_released * extent;
+		effects.push(HookEffect::FireResult(fire_amount));
+		if temperature > tuning.minimum_temperature {
+			effects.push(HookEffect::FireExpose(temperature));
 		}
+		if radiation_released > 0.0 {
+			effects.push(HookEffect::RadiationBurn(radiation_released));
+		}
+		Ok(if fire_amount > 0.0 {
+			ReactionReturn::REACTING
+		} else {
+			ReactionReturn::NO_REACTION
+		})
 	})
 }
@@ -16,7 +16,11 @@ use auxtools::*;
 
 use rayon::prelude::*;
 
-use crate::{constants::*, gas::Mixture, GasArena};
+use crate::{
+	constants::*,
+	gas::{mix_id, Mixture},
+	GasArena,
+};
 
 use fxhash::FxBuildHasher;
 
@@ -29,7 +33,11 @@ use petgraph::{graph::NodeIndex, stable_graph::StableDiGraph, visit::EdgeRef, Di
 use indexmap::IndexMap;
 
 use std::time::Duration;
-use std::{mem::drop, sync::atomic::AtomicU64};
+use std::{
+	collections::{HashMap, HashSet, VecDeque},
+	mem::drop,
+	sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
 
 bitflags! {
 	#[derive(Default)]
@@ -292,6 +300,36 @@ impl TurfGases {
 		self.graph.neighbors(index)
 	}
 
+	// Turfs diagonal to this one on the same z-level, that gas can reach this one from through both of the turfs in between.
+	pub fn diagonal_node_ids(
+		&self,
+		index: NodeIndex,
+		max_x: i32,
+		max_y: i32,
+	) -> impl Iterator<Item = NodeIndex> + '_ {
+		let id = self.get(index).map(|tmix| tmix.id);
+		// (north or south, east or west), as adjacent_tile_id indices
+		[(0, 2), (0, 3), (1, 2), (1, 3)]
+			.into_iter()
+			.filter_map(move |(vertical, horizontal)| {
+				let id = id?;
+				let node = |turf: TurfID| self.map.get(&turf).copied();
+				let first = node(adjacent_tile_id(vertical, id, max_x, max_y))?;
+				let second = node(adjacent_tile_id(horizontal, id, max_x, max_y))?;
+				let diagonal = node(adjacent_tile_id(
+					horizontal,
+					adjacent_tile_id(vertical, id, max_x, max_y),
+					max_x,
+					max_y,
+				))?;
+				(self.graph.contains_edge(first, index)
+					&& self.graph.contains_edge(second, index)
+					&& self.graph.contains_edge(diagonal, first)
+					&& self.graph.contains_edge(diagonal, second))
+				.then_some(diagonal)
+			})
+	}
+
 	#[allow(unused)]
 	pub fn adjacent_turf_ids(&self, index: NodeIndex) -> impl Iterator<Item = TurfID> + '_ {
 		self.graph
@@ -377,6 +415,20 @@ static DIRTY_TURFS: Mutex<Option<IndexMap<TurfID, DirtyFlags, FxBuildHasher>>> =
 
 static ANY_TURF_DIRTY: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
 
+// Excited group membership as of the last processing run, for debug overlays.
+static EXCITED_GROUPS: RwLock<Option<ExcitedGroups>> = const_rwlock(None);
+
+static NEXT_EXCITED_GROUP_ID: AtomicUsize = AtomicUsize::new(1);
+
+// Whether automatic processing is paused, see auxmos_pause_atmos.
+static ATMOS_PAUSED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+#[derive(Default)]
+struct ExcitedGroups {
+	members: HashMap<TurfID, usize, FxBuildHasher>,
+	sizes: HashMap<usize, usize, FxBuildHasher>,
+}
+
 pub fn wait_for_tasks() {
 	match TASKS.try_write_for(Duration::from_secs(5)) {
 		Some(_) => (),
@@ -405,6 +457,29 @@ fn _shutdown_turfs() {
 	*DIRTY_TURFS.lock() = None;
 	*TURF_GASES.write() = None;
 	*PLANETARY_ATMOS.write() = None;
+	*EXCITED_GROUPS.write() = None;
+	NEXT_EXCITED_GROUP_ID.store(1, Ordering::Relaxed);
+	ATMOS_PAUSED.store(false, Ordering::Relaxed);
+}
+
+/// Whether automatic processing is paused. Processing entry points check this and do nothing while it is.
+pub fn atmos_paused() -> bool {
+	ATMOS_PAUSED.load(Ordering::Relaxed)
+}
+
+/// Pauses automatic turf processing, for admin events and the like. Runs already going finish as usual.
+/// Gas mixtures can still be read and changed from byond as normal.
+#[hook("/proc/auxmos_pause_atmos")]
+fn _hook_pause_atmos() {
+	ATMOS_PAUSED.store(true, Ordering::Relaxed);
+	Ok(Value::null())
+}
+
+/// Resumes automatic turf processing after auxmos_pause_atmos.
+#[hook("/proc/auxmos_resume_atmos")]
+fn _hook_resume_atmos() {
+	ATMOS_PAUSED.store(false, Ordering::Relaxed);
+	Ok(Value::null())
 }
 
 fn set_turfs_dirty(b: bool) {
@@ -451,6 +526,110 @@ where
 	f(PLANETARY_ATMOS.write().as_mut().unwrap())
 }
 
+/// Replaces the recorded excited groups with the ones formed this run.
+/// A group keeps the id most of its members had last run, so ids stay the same for as long as the group exists.
+fn record_excited_groups(groups: Vec<Vec<TurfID>>) {
+	let mut lock = EXCITED_GROUPS.write();
+	let old = lock.take().unwrap_or_default();
+	let mut new = ExcitedGroups::default();
+	for members in groups {
+		let mut previous_ids: HashMap<usize, usize, FxBuildHasher> = Default::default();
+		for turf in &members {
+			if let Some(&group) = old.members.get(turf) {
+				*previous_ids.entry(group).or_default() += 1;
+			}
+		}
+		let group = previous_ids
+			.into_iter()
+			.filter(|(group, _)| !new.sizes.contains_key(group))
+			.max_by_key(|&(group, count)| (count, std::cmp::Reverse(group)))
+			.map_or_else(
+				|| NEXT_EXCITED_GROUP_ID.fetch_add(1, Ordering::Relaxed),
+				|(group, _)| group,
+			);
+		new.sizes.insert(group, members.len());
+		new.members
+			.extend(members.into_iter().map(|turf| (turf, group)));
+	}
+	*lock = Some(new);
+}
+
+/// The turf each simulated turf's gas mixture belongs to, by gas mixture index.
+pub fn turf_air_holders() -> HashMap<usize, TurfID, FxBuildHasher> {
+	with_turf_gases_read(|arena| {
+		arena
+			.map
+			.values()
+			.filter_map(|&idx| arena.get(idx))
+			.map(|turf| (turf.mix, turf.id))
+			.collect()
+	})
+}
+
+/// Returns the id of the excited group the turf was in last processing run, if any.
+pub fn excited_group_id(turf_id: TurfID) -> Option<usize> {
+	EXCITED_GROUPS
+		.read()
+		.as_ref()
+		.and_then(|groups| groups.members.get(&turf_id).copied())
+}
+
+/// Returns how many turfs are in the given excited group, 0 if it doesn't exist.
+pub fn excited_group_size(id: usize) -> usize {
+	EXCITED_GROUPS
+		.read()
+		.as_ref()
+		.and_then(|groups| groups.sizes.get(&id).copied())
+		.unwrap_or(0)
+}
+
+/// Every turf gas can get to from the given one through the atmos adjacency graph, nearest first, the start included.
+/// Stops after `max` turfs. Empty if the turf isn't simulated.
+pub fn connected_turfs(start: TurfID, max: usize) -> Vec<TurfID> {
+	with_turf_gases_read(|arena| {
+		let mut connected = Vec::new();
+		let start = match arena.map.get(&start) {
+			Some(&start) => start,
+			None => return connected,
+		};
+		let mut seen = HashSet::from([start]);
+		let mut queue = VecDeque::from([start]);
+		while let Some(index) = queue.pop_front() {
+			if connected.len() >= max {
+				break;
+			}
+			if let Some(tmix) = arena.get(index) {
+				connected.push(tmix.id);
+			}
+			for adjacent in arena.adjacent_node_ids(index) {
+				if seen.insert(adjacent) {
+					queue.push_back(adjacent);
+				}
+			}
+		}
+		connected
+	})
+}
+
+/// Args: (max). Returns: a list of every turf gas can get to from this one, nearest first and this one included, up to max turfs.
+/// Uses the adjacency auxmos already has, so it's only as up to date as the last processing run.
+#[hook("/turf/proc/auxmos_connected_region")]
+fn _hook_connected_region(max: Value) {
+	let max = max.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let region = List::new();
+	for id in connected_turfs(unsafe { src.raw.data.id }, max.max(0.0) as usize) {
+		region.append(unsafe { Value::turf_by_id_unchecked(id) });
+	}
+	Ok(Value::from(region))
+}
+
 fn rebuild_turf_graph() -> Result<(), Runtime> {
 	with_dirty_turfs(|dirty_turfs| {
 		for (&t, _) in dirty_turfs
@@ -581,6 +760,52 @@ fn _hook_infos() {
 	Ok(Value::null())
 }
 
+/// Conducts heat between the two gas mixtures with the given IDs, using the same math as gas sharing, without moving any gas.
+/// Temperatures won't go below TCMB.
+/// # Errors
+/// If either gas mixture doesn't exist.
+pub fn conduct_heat(a: usize, b: usize, coefficient: f32) -> Result<(), Runtime> {
+	GasArena::with_gas_mixtures_mut(a, b, |a_mix, b_mix| {
+		a_mix.temperature_share(b_mix, coefficient);
+		Ok(())
+	})
+}
+
+/// Args: (turf, coefficient). Conducts heat between this turf's air and the other turf's, without moving any gas.
+#[hook("/turf/proc/auxmos_conduct_heat")]
+fn _hook_conduct_heat(other: Value, coefficient: Value) {
+	let coefficient = coefficient.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	conduct_heat(
+		mix_id(&src.get(byond_string!("air"))?)?,
+		mix_id(&other.get(byond_string!("air"))?)?,
+		coefficient,
+	)?;
+	Ok(Value::null())
+}
+
+/// Returns: list(group id, group size) for the excited group this turf was in last processing run, or null if it wasn't in one.
+/// Group ids stay the same for as long as the group exists.
+#[hook("/turf/proc/auxmos_excited_group")]
+fn _hook_excited_group() {
+	let id = unsafe { src.raw.data.id };
+	match excited_group_id(id) {
+		Some(group) => {
+			let ret = List::new();
+			ret.append(Value::from(group as f32));
+			ret.append(Value::from(excited_group_size(group) as f32));
+			Ok(Value::from(ret))
+		}
+		None => Ok(Value::null()),
+	}
+}
+
 // gas_overlays: list( GAS_ID = list( VIS_FACTORS = OVERLAYS )) got it? I don't
 /// Updates the visual overlays for the given turf.
 /// Will use a cached overlay list if one exists.
@@ -725,6 +725,7 @@ fn send_pressure_differences(
 
 pub fn equalize(
 	equalize_hard_turf_limit: usize,
+	min_moles: f32,
 	high_pressure_turfs: &std::collections::BTreeSet<NodeIndex>,
 	_planet_enabled: bool,
 ) -> usize {
@@ -747,7 +748,7 @@ pub fn equalize(
 
 				let is_unshareable = GasArena::with_all_mixtures(|all_mixtures| {
 					let our_moles = all_mixtures[cur_mixture.mix].read().total_moles();
-					our_moles < 10.0
+					our_moles < min_moles
 						|| arena.adjacent_mixes(cur_index, all_mixtures).all(|lock| {
 							(lock.read().total_moles() - our_moles).abs()
 								< MINIMUM_MOLES_DELTA_TO_MOVE
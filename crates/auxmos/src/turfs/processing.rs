@@ -2,7 +2,14 @@ use auxtools::*;
 
 use super::*;
 
-use crate::GasArena;
+use crate::{
+	gas::{
+		gas_idx_from_value,
+		mixture::{sim_floors, SimFloors},
+		total_num_gases, WorkerPhase,
+	},
+	GasArena,
+};
 
 use auxcallback::{byond_callback_sender, process_callbacks_for_millis};
 
@@ -10,23 +17,60 @@ use parking_lot::{Once, RwLock};
 
 use tinyvec::TinyVec;
 
+use dashmap::{DashMap, DashSet};
+
 use std::{
 	collections::{BTreeMap, BTreeSet, HashSet, VecDeque},
+	ops::Range,
+	sync::atomic::{AtomicBool, AtomicU64, AtomicUsize},
 	time::Instant,
 };
 
 static INIT_TURF: Once = Once::new();
 
+// How long turf sharing can take per run, in milliseconds. 0 means no limit.
+static TURF_BUDGET_MS: AtomicU64 = AtomicU64::new(0);
+
+// Where in the turf map a sharing run with a budget picks up, so turfs it ran out of time for go first next run.
+static TURF_CURSOR: AtomicUsize = AtomicUsize::new(0);
+
+// How many turfs a sharing run with a budget shares at a time before checking the time again.
+const DEADLINE_CHUNK_SIZE: usize = 4096;
+
+// Whether sharing checks that it conserved moles and energy, see auxmos_enable_conservation_checks.
+static CONSERVATION_CHECKS: AtomicBool = AtomicBool::new(false);
+
+// How far, relative to the total, moles or energy can drift over one share before it's reported.
+const CONSERVATION_TOLERANCE: f64 = 1.0e-4;
+
+// The global proc told about turfs that start being processed, see auxmos_set_excited_callback.
+static EXCITED_CALLBACK: RwLock<Option<String>> = const_rwlock(None);
+
+// Whether processing keeps track of which turfs it processed, see auxmos_active_turfs.
+static TRACK_ACTIVE_TURFS: AtomicBool = AtomicBool::new(false);
+
+// Every turf processed in the last finished processing run, only replaced once a run is done.
+static ACTIVE_TURFS: RwLock<Vec<TurfID>> = const_rwlock(Vec::new());
+
 lazy_static::lazy_static! {
 	static ref TURF_CHANNEL: (
-		flume::Sender<Box<SSairInfo>>,
-		flume::Receiver<Box<SSairInfo>>
+		flume::Sender<(Box<SSairInfo>, WorkerPhase)>,
+		flume::Receiver<(Box<SSairInfo>, WorkerPhase)>
 	) = flume::bounded(1);
+	// Turfs that were being processed when last checked, so we can tell when one goes from idle to excited.
+	static ref EXCITED_TURFS: DashSet<TurfID, FxBuildHasher> = DashSet::with_hasher(FxBuildHasher::default());
+	// Turfs that became excited since the excited callback was last sent.
+	static ref NEWLY_EXCITED_TURFS: Mutex<Vec<TurfID>> = Mutex::new(Vec::new());
+	// Turfs kept processing whether or not their gas is moving, to how many more processing runs they're kept for.
+	static ref KEPT_ACTIVE_TURFS: DashMap<TurfID, u32, FxBuildHasher> = DashMap::with_hasher(FxBuildHasher::default());
+	// Turfs processed so far in the processing run that's going on.
+	static ref PASS_ACTIVE_TURFS: DashSet<TurfID, FxBuildHasher> = DashSet::with_hasher(FxBuildHasher::default());
 }
 
 #[derive(Copy, Clone)]
 #[allow(unused)]
 struct SSairInfo {
+	diagonal_sharing: Option<DiagonalSharing>,
 	fdm_max_steps: i32,
 	equalize_turf_limit: usize,
 	equalize_hard_turf_limit: usize,
@@ -35,11 +79,83 @@ struct SSairInfo {
 	planet_enabled: bool,
 }
 
-fn with_processing_callback_receiver<T>(f: impl Fn(&flume::Receiver<Box<SSairInfo>>) -> T) -> T {
+// Equalization tuning that can be changed at runtime, see auxmos_set_monstermos_tuning.
+// `max_turfs` and `min_moles` are only used by fastmos's equalization.
+#[derive(Copy, Clone)]
+struct EqualizeTuning {
+	// If set, used instead of SSair's equalize_hard_turf_limit.
+	#[cfg_attr(not(feature = "fastmos"), allow(dead_code))]
+	max_turfs: Option<usize>,
+	// Turfs with a pressure difference to their neighbors above this get equalized.
+	pressure_threshold: f32,
+	// Turfs with less moles than this don't get equalized.
+	#[cfg_attr(not(feature = "fastmos"), allow(dead_code))]
+	min_moles: f32,
+}
+
+static EQUALIZE_TUNING: RwLock<EqualizeTuning> = const_rwlock(EqualizeTuning {
+	max_turfs: None,
+	pressure_threshold: 5.0,
+	min_moles: 10.0,
+});
+
+fn equalize_tuning() -> EqualizeTuning {
+	*EQUALIZE_TUNING.read()
+}
+
+// How many moles each gas has to differ by between neighbors for them to share, when it's the biggest difference,
+// indexed by gas; see auxmos_set_gas_process_thresholds. Empty if no gas has its own threshold.
+static GAS_PROCESS_THRESHOLDS: RwLock<Vec<f32>> = const_rwlock(Vec::new());
+
+// How much of a turf's gas each diagonal neighbor gets, if turfs share diagonally; see auxmos_set_diagonal_sharing.
+static DIAGONAL_COEFFICIENT: RwLock<Option<f32>> = const_rwlock(None);
+
+// Diagonal sharing as handed to the processing thread, along with the world size needed to find the diagonals.
+#[derive(Copy, Clone)]
+pub struct DiagonalSharing {
+	// Already adjusted for the extra distance to the diagonal.
+	coefficient: f32,
+	max_x: i32,
+	max_y: i32,
+}
+
+// Main thread only, since it reads the world size.
+fn diagonal_sharing() -> Result<Option<DiagonalSharing>, Runtime> {
+	let coefficient = match *DIAGONAL_COEFFICIENT.read() {
+		Some(coefficient) => coefficient,
+		None => return Ok(None),
+	};
+	let world = Value::world();
+	let max_x = world.get_number(byond_string!("maxx")).map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})? as i32;
+	let max_y = world.get_number(byond_string!("maxy")).map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})? as i32;
+	Ok(Some(DiagonalSharing {
+		coefficient,
+		max_x,
+		max_y,
+	}))
+}
+
+fn with_processing_callback_receiver<T>(
+	f: impl Fn(&flume::Receiver<(Box<SSairInfo>, WorkerPhase)>) -> T,
+) -> T {
 	f(&TURF_CHANNEL.1)
 }
 
-fn processing_callbacks_sender() -> flume::Sender<Box<SSairInfo>> {
+fn processing_callbacks_sender() -> flume::Sender<(Box<SSairInfo>, WorkerPhase)> {
 	TURF_CHANNEL.0.clone()
 }
 
@@ -75,6 +191,11 @@ fn _finish_process_turfs() {
 
 #[hook("/datum/controller/subsystem/air/proc/process_turfs_auxtools")]
 fn _process_turf_notify() {
+	if atmos_paused() {
+		return Ok(Value::null());
+	}
+	crate::reaction::advance_reaction_tick();
+	crate::gas::stats::end_tick();
 	let sender = processing_callbacks_sender();
 	let fdm_max_steps = src
 		.get_number(byond_string!("share_max_steps"))
@@ -103,14 +224,401 @@ fn _process_turf_notify() {
 		.get_number(byond_string!("planet_equalize_enabled"))
 		.unwrap_or(1.0)
 		!= 0.0;
-	drop(sender.try_send(Box::new(SSairInfo {
-		fdm_max_steps,
-		equalize_turf_limit,
-		equalize_hard_turf_limit,
-		equalize_enabled,
-		group_pressure_goal,
-		planet_enabled,
+	let diagonal_sharing = diagonal_sharing()?;
+	// if the worker's still busy, the send fails and the phase is dropped right away
+	drop(sender.try_send((
+		Box::new(SSairInfo {
+			diagonal_sharing,
+			fdm_max_steps,
+			equalize_turf_limit,
+			equalize_hard_turf_limit,
+			equalize_enabled,
+			group_pressure_goal,
+			planet_enabled,
+		}),
+		WorkerPhase::begin(),
+	)));
+	Ok(Value::null())
+}
+
+/// Args: (enabled, coeff). Turns sharing with diagonal neighbors on or off. Only diagonals that gas can get to through both turfs in between count.
+/// Diagonals share at the cardinal rate times coeff, divided by sqrt(2) for the extra distance.
+/// coeff must be at least 0 and less than 1/sqrt(2), about 0.707, so a turf with every neighbor, up and down included, keeps some of its own gas.
+/// Pressure differences, and so things getting thrown around, still only come from cardinal neighbors.
+#[hook("/proc/auxmos_set_diagonal_sharing")]
+fn _hook_set_diagonal_sharing(enabled: Value, coeff: Value) {
+	if enabled.as_number().map_or(true, |enabled| enabled == 0.0) {
+		*DIAGONAL_COEFFICIENT.write() = None;
+		return Ok(Value::null());
+	}
+	let coeff = coeff.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if !(0.0..std::f32::consts::FRAC_1_SQRT_2).contains(&coeff) {
+		return Err(runtime!("Invalid diagonal sharing coefficient: {}", coeff));
+	}
+	*DIAGONAL_COEFFICIENT.write() =
+		Some(GAS_DIFFUSION_CONSTANT * coeff * std::f32::consts::FRAC_1_SQRT_2);
+	Ok(Value::null())
+}
+
+#[shutdown]
+fn _shutdown_excited_callback() {
+	*EXCITED_CALLBACK.write() = None;
+	EXCITED_TURFS.clear();
+	NEWLY_EXCITED_TURFS.lock().clear();
+	KEPT_ACTIVE_TURFS.clear();
+}
+
+/// Keeps the turf processing for the next given amount of processing runs, even if its gas is at rest.
+/// If it's already being kept active for longer, that's kept. Zero runs does nothing.
+pub fn keep_turf_active(id: TurfID, runs: u32) {
+	if runs == 0 {
+		return;
+	}
+	let mut kept = KEPT_ACTIVE_TURFS.entry(id).or_insert(0);
+	*kept = (*kept).max(runs);
+}
+
+// Counts down every turf being kept active by one processing run, letting go of the ones that are done.
+fn tick_kept_active_turfs() {
+	KEPT_ACTIVE_TURFS.retain(|_, runs| {
+		*runs = runs.saturating_sub(1);
+		*runs > 0
+	});
+}
+
+/// Args: (ticks). Keeps this turf processing for the next given amount of processing runs, even if its gas is at rest,
+/// e.g. for a running vent. Counts down once every processing run.
+#[hook("/turf/proc/auxmos_keep_active")]
+fn _keep_active_hook(ticks: Value) {
+	let ticks = ticks.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if ticks >= 1.0 {
+		keep_turf_active(unsafe { src.raw.data.id }, ticks as u32);
+	}
+	Ok(Value::null())
+}
+
+/// Every turf processed in the last finished processing run, for running custom logic on active turfs.
+/// It's only swapped out once a run is over, so it's never a mix of two runs. Empty until tracking is turned on by `auxmos_active_turfs`.
+pub fn active_turfs() -> Vec<TurfID> {
+	ACTIVE_TURFS.read().clone()
+}
+
+// Makes the turfs processed in the run that just finished the ones `active_turfs` gives, all at once.
+fn publish_active_turfs() {
+	if !TRACK_ACTIVE_TURFS.load(Ordering::Relaxed) {
+		return;
+	}
+	let turfs = PASS_ACTIVE_TURFS.iter().map(|id| *id).collect();
+	PASS_ACTIVE_TURFS.clear();
+	*ACTIVE_TURFS.write() = turfs;
+}
+
+/// Returns: a list of every turf processed in the last finished processing run.
+/// Turfs aren't tracked until this is first called, so that call's list is empty.
+#[hook("/proc/auxmos_active_turfs")]
+fn _hook_active_turfs() {
+	TRACK_ACTIVE_TURFS.store(true, Ordering::Relaxed);
+	let turf_list = List::new();
+	for id in active_turfs() {
+		turf_list.append(unsafe { Value::turf_by_id_unchecked(id) });
+	}
+	Ok(Value::from(turf_list))
+}
+
+#[shutdown]
+fn _shutdown_active_turfs() {
+	TRACK_ACTIVE_TURFS.store(false, Ordering::Relaxed);
+	PASS_ACTIVE_TURFS.clear();
+	ACTIVE_TURFS.write().clear();
+}
+
+/// Args: (proc_path). Sets the global proc, given as text, that gets called with a list of turfs whenever turfs go from idle to being processed.
+/// It's called once per processing run with every turf that got excited since the last one. Null stops the calls.
+#[hook("/proc/auxmos_set_excited_callback")]
+fn _hook_set_excited_callback(callback: Value) {
+	let callback = callback.as_string().ok();
+	if let Some(path) = &callback {
+		if Proc::find(path.as_str()).is_none() {
+			return Err(runtime!("Excited turf callback {} doesn't exist!", path));
+		}
+	}
+	EXCITED_TURFS.clear();
+	NEWLY_EXCITED_TURFS.lock().clear();
+	*EXCITED_CALLBACK.write() = callback;
+	Ok(Value::null())
+}
+
+// Records whether the turf is being processed, queueing it for the excited callback if it wasn't before.
+fn track_excitement(id: TurfID, processing: bool) {
+	if processing {
+		if EXCITED_TURFS.insert(id) {
+			NEWLY_EXCITED_TURFS.lock().push(id);
+		}
+	} else {
+		EXCITED_TURFS.remove(&id);
+	}
+}
+
+// Sends every turf excited since last time to the excited callback, all in one call.
+fn send_excited_turfs() {
+	let turfs = std::mem::take(&mut *NEWLY_EXCITED_TURFS.lock());
+	if turfs.is_empty() {
+		return;
+	}
+	let sender = byond_callback_sender();
+	drop(sender.try_send(Box::new(move || {
+		let path = match EXCITED_CALLBACK.read().clone() {
+			Some(path) => path,
+			None => return Ok(()),
+		};
+		let callback = Proc::find(path.as_str())
+			.ok_or_else(|| runtime!("Excited turf callback {} doesn't exist!", path))?;
+		let turf_list = List::new();
+		for id in turfs {
+			turf_list.append(unsafe { Value::turf_by_id_unchecked(id) });
+		}
+		callback.call(&[&Value::from(turf_list)])?;
+		Ok(())
 	})));
+}
+
+/// Args: (ms). Sets how long turf sharing can take per processing run. Once it's used up, no more sharing steps start that run,
+/// though the first always runs in full. 0 removes the limit.
+#[hook("/proc/auxmos_set_atmos_budget")]
+fn _hook_set_atmos_budget(ms: Value) {
+	let ms = ms.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	TURF_BUDGET_MS.store(ms.max(0.0) as u64, Ordering::Relaxed);
+	Ok(Value::null())
+}
+
+/// Args: (min_moles, min_temp). Sets the moles below which gases are thrown away and the lowest temperature
+/// gas and turfs can have, normally `GAS_MIN_MOLES` and `TCMB`.
+/// Changing these mid-round can make gas appear or vanish, or temperatures jump, for a tick or so.
+#[hook("/proc/auxmos_set_sim_floors")]
+fn _hook_set_sim_floors(min_moles: Value, min_temp: Value) {
+	let min_moles = min_moles.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let min_temp = min_temp.as_number().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-number value as number {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	if !min_moles.is_finite() || min_moles < 0.0 || !min_temp.is_finite() || min_temp <= 0.0 {
+		return Err(runtime!(
+			"Invalid simulation floors: {} moles, {} kelvin",
+			min_moles,
+			min_temp
+		));
+	}
+	crate::gas::mixture::set_sim_floors(SimFloors {
+		min_moles,
+		min_temperature: min_temp,
+	});
+	Ok(Value::null())
+}
+
+/// Args: (list). Takes an associative list of any of "max_turfs", "pressure_threshold" and "min_moles".
+/// Sets how many turfs equalization will handle per pass (0 to use SSair's equalize_hard_turf_limit again),
+/// the pressure difference needed to trigger it and the moles a turf needs to be equalized.
+/// Only fastmos's equalization uses "max_turfs" and "min_moles"; monstermos and putnamos ignore them.
+#[hook("/proc/auxmos_set_monstermos_tuning")]
+fn _hook_set_monstermos_tuning(params: Value) {
+	let list = params.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut tuning = equalize_tuning();
+	for i in 1..=list.len() {
+		let key = list.get(i)?;
+		let amt = list.get(key.clone())?.as_number().map_err(|_| {
+			runtime!(
+				"Attempt to interpret non-number value as number {} {}:{}",
+				std::file!(),
+				std::line!(),
+				std::column!()
+			)
+		})?;
+		if !amt.is_finite() || amt < 0.0 {
+			return Err(runtime!("Invalid monstermos tuning value: {}", amt));
+		}
+		match key.as_string()?.as_str() {
+			"max_turfs" => tuning.max_turfs = Some(amt as usize).filter(|&n| n > 0),
+			"pressure_threshold" => tuning.pressure_threshold = amt,
+			"min_moles" => tuning.min_moles = amt,
+			other => return Err(runtime!("Unknown monstermos tuning parameter: {}", other)),
+		}
+	}
+	*EQUALIZE_TUNING.write() = tuning;
+	Ok(Value::null())
+}
+
+/// Args: (list). Takes an associative list of gas IDs to moles. Neighboring turfs whose biggest difference is in one of those gases
+/// only share once that gas differs by at least that many moles, so tiles that only differ by a trace gas can be left alone.
+/// Other gases, and every gas if the list is empty, use the usual MINIMUM_MOLES_DELTA_TO_MOVE. Replaces any earlier thresholds.
+#[hook("/proc/auxmos_set_gas_process_thresholds")]
+fn _hook_set_gas_process_thresholds(params: Value) {
+	let list = params.as_list().map_err(|_| {
+		runtime!(
+			"Attempt to interpret non-list value as list {} {}:{}",
+			std::file!(),
+			std::line!(),
+			std::column!()
+		)
+	})?;
+	let mut thresholds = Vec::new();
+	for i in 1..=list.len() {
+		let key = list.get(i)?;
+		let amt = list.get(key.clone())?.as_number().map_err(|_| {
+			runtime!(
+				"Attempt to interpret non-number value as number {} {}:{}",
+				std::file!(),
+				std::line!(),
+				std::column!()
+			)
+		})?;
+		if !amt.is_finite() || amt < 0.0 {
+			return Err(runtime!("Invalid gas process threshold: {}", amt));
+		}
+		if thresholds.is_empty() {
+			thresholds = vec![MINIMUM_MOLES_DELTA_TO_MOVE; total_num_gases()];
+		}
+		if let Some(threshold) = thresholds.get_mut(gas_idx_from_value(&key)?) {
+			*threshold = amt;
+		}
+	}
+	*GAS_PROCESS_THRESHOLDS.write() = thresholds;
+	Ok(Value::null())
+}
+
+#[shutdown]
+fn _shutdown_gas_process_thresholds() {
+	GAS_PROCESS_THRESHOLDS.write().clear();
+}
+
+/// Runs sharing, excited group equalization, reactions and visual updates for this turf and its neighbors right now,
+/// instead of waiting for the next processing run. Can't be used while turf processing is running.
+/// Those turfs only share with each other, not with anything further out.
+#[hook("/turf/proc/auxmos_process_now")]
+fn _process_turf_now_hook() {
+	let task_lock = TASKS
+		.try_write()
+		.ok_or_else(|| runtime!("Can't process a turf while turf processing is running!"))?;
+	if check_turfs_dirty() {
+		rebuild_turf_graph()?;
+	}
+	let id = unsafe { src.raw.data.id };
+	let diagonal_sharing = diagonal_sharing()?;
+	let pressure_goal = Value::globals()
+		.get(byond_string!("SSair"))?
+		.get_number(byond_string!("excited_group_pressure_goal"))
+		.unwrap_or(0.5);
+	let (cell_ids, shared) = with_turf_gases_read(|arena| {
+		let index = *arena
+			.map
+			.get(&id)
+			.ok_or_else(|| runtime!("Turf {} isn't being simulated!", id))?;
+		let cells = std::iter::once(index)
+			.chain(arena.adjacent_node_ids(index))
+			.collect::<Vec<_>>();
+		let cell_ids = cells
+			.iter()
+			.filter_map(|&idx| Some(arena.get(idx)?.id))
+			.collect::<Vec<_>>();
+		// Every one of these shares, and only with each other, so no gas is made or lost at the edges.
+		let sharing = cells
+			.iter()
+			.filter_map(|&idx| Some((idx, arena.get(idx).filter(|turf| turf.enabled())?.id)))
+			.collect::<Vec<_>>();
+		let sharing_ids = sharing.iter().map(|&(_, id)| id).collect::<Vec<_>>();
+		let floors = sim_floors();
+		let shared = GasArena::with_all_mixtures(|all_mixtures| {
+			// all of these need to be done before any of them are shared, same as in fdm
+			let turfs_to_save = sharing
+				.iter()
+				.filter_map(|&(idx, _)| {
+					process_cell(
+						idx,
+						all_mixtures,
+						arena,
+						diagonal_sharing,
+						&SharingScope::Turfs(&sharing_ids),
+						floors,
+					)
+				})
+				.collect::<Vec<_>>();
+			turfs_to_save
+				.into_iter()
+				.filter_map(|(i, end_gas, pressure_diffs, taken)| {
+					share_cell(
+						i,
+						&end_gas,
+						pressure_diffs,
+						taken,
+						all_mixtures,
+						arena,
+						floors,
+					)
+				})
+				.filter_map(|(i, pressure_diffs, max_diff)| {
+					Some((i, arena.get(i)?.id, pressure_diffs, max_diff))
+				})
+				.collect::<Vec<_>>()
+		});
+		Ok((cell_ids, shared))
+	})?;
+	let pressure_threshold = equalize_tuning().pressure_threshold;
+	let low_pressure_turfs = shared
+		.iter()
+		.filter(|&&(_, _, _, max_diff)| max_diff <= pressure_threshold)
+		.map(|&(i, _, _, _)| i)
+		.collect::<BTreeSet<_>>();
+	// membership only gets recorded on full runs, a partial one would drop every other group
+	excited_group_processing(pressure_goal, &low_pressure_turfs);
+	drop(task_lock);
+	for (_, turf_id, pressure_diffs, max_diff) in &shared {
+		if *max_diff > pressure_threshold {
+			consider_pressure_differences(*turf_id, pressure_diffs)?;
+		}
+	}
+	for turf_id in cell_ids {
+		let turf = unsafe { Value::turf_by_id_unchecked(turf_id) };
+		turf.get(byond_string!("air"))?.call("react", &[&turf])?;
+		update_visuals(turf)?;
+	}
 	Ok(Value::null())
 }
 
@@ -121,15 +629,23 @@ fn _process_turf_start() -> Result<(), String> {
 		#[allow(unused)]
 		rayon::spawn(|| loop {
 			//this will block until process_turfs is called
-			let info = with_processing_callback_receiver(|receiver| receiver.recv().unwrap());
+			let (info, phase) =
+				with_processing_callback_receiver(|receiver| receiver.recv().unwrap());
 			let task_lock = TASKS.read();
 			let sender = byond_callback_sender();
 			let mut stats: Vec<Box<dyn Fn() -> Result<(), Runtime> + Send + Sync>> =
 				Default::default();
 			let (low_pressure_turfs, high_pressure_turfs) = {
 				let start_time = Instant::now();
-				let (low_pressure_turfs, high_pressure_turfs) =
-					fdm(info.fdm_max_steps, info.equalize_enabled);
+				let (low_pressure_turfs, high_pressure_turfs) = process_turfs_with_deadline(
+					TURF_BUDGET_MS.load(Ordering::Relaxed),
+					info.fdm_max_steps,
+					info.equalize_enabled,
+					info.diagonal_sharing,
+				);
+				send_excited_turfs();
+				tick_kept_active_turfs();
+				publish_active_turfs();
 				let bench = start_time.elapsed().as_millis();
 				let (lpt, hpt) = (low_pressure_turfs.len(), high_pressure_turfs.len());
 				stats.push(Box::new(move || {
@@ -158,8 +674,9 @@ fn _process_turf_start() -> Result<(), String> {
 			};
 			{
 				let start_time = Instant::now();
-				let processed_turfs =
+				let (processed_turfs, groups) =
 					excited_group_processing(info.group_pressure_goal, &low_pressure_turfs);
+				record_excited_groups(groups);
 				let bench = start_time.elapsed().as_millis();
 				stats.push(Box::new(move || {
 					let ssair = auxtools::Value::globals().get(byond_string!("SSair"))?;
@@ -190,8 +707,10 @@ fn _process_turf_start() -> Result<(), String> {
 				let processed_turfs = {
 					#[cfg(feature = "fastmos")]
 					{
+						let tuning = equalize_tuning();
 						super::katmos::equalize(
-							info.equalize_hard_turf_limit,
+							tuning.max_turfs.unwrap_or(info.equalize_hard_turf_limit),
+							tuning.min_moles,
 							&high_pressure_turfs,
 							info.planet_enabled,
 						)
@@ -228,7 +747,7 @@ fn _process_turf_start() -> Result<(), String> {
 			}
 			{
 				let start_time = Instant::now();
-				post_process();
+				post_process(&low_pressure_turfs, &high_pressure_turfs);
 				let bench = start_time.elapsed().as_millis();
 				stats.push(Box::new(move || {
 					let ssair = auxtools::Value::globals().get(byond_string!("SSair"))?;
@@ -259,16 +778,20 @@ fn _process_turf_start() -> Result<(), String> {
 			}
 			{
 				//let it gooooo
-				rayon::spawn(planet_process);
+				// begun while this one's still alive, so there's no gap
+				let planet_phase = WorkerPhase::begin();
+				rayon::spawn(move || planet_process(planet_phase));
 			}
 			drop(task_lock);
+			drop(phase);
 		});
 	});
 	Ok(())
 }
 
-fn planet_process() {
+fn planet_process(phase: WorkerPhase) {
 	let task_lock = TASKS.read();
+	let floors = sim_floors();
 	with_turf_gases_read(|arena| {
 		GasArena::with_all_mixtures(|all_mixtures| {
 			with_planetary_atmos(|map| {
@@ -287,7 +810,7 @@ fn planet_process() {
 							let comparison = gas_read.compare(planet_atmos);
 							let has_temp_difference = gas_read.temperature_compare(planet_atmos);
 							if let Some(mut gas) = (has_temp_difference
-								|| (comparison > GAS_MIN_MOLES))
+								|| (comparison > floors.min_moles))
 								.then(|| {
 									parking_lot::lock_api::RwLockUpgradableReadGuard::try_upgrade(
 										gas_read,
@@ -296,18 +819,43 @@ fn planet_process() {
 								})
 								.flatten()
 							{
+								let before = crate::gas::stats::enabled().then(|| gas.moles_copy());
 								if comparison > 0.1 || has_temp_difference {
-									gas.share_ratio(planet_atmos, GAS_DIFFUSION_CONSTANT);
+									gas.share_ratio(planet_atmos, GAS_DIFFUSION_CONSTANT, floors);
 								} else {
 									gas.copy_from_mutable(planet_atmos);
 								}
+								if let Some(before) = before {
+									crate::gas::stats::record_difference(&before, &gas);
+								}
 							}
 						}
 					})
 			})
 		})
 	});
-	drop(task_lock)
+	drop(task_lock);
+	drop(phase);
+}
+
+// Whether two neighboring mixes differ enough to share. With per-gas thresholds, the biggest difference decides.
+fn differs_enough(gas: &Mixture, other: &Mixture, thresholds: &[f32]) -> bool {
+	if gas.temperature_compare(other) {
+		return true;
+	}
+	if thresholds.is_empty() {
+		return gas.compare_with(other, MINIMUM_MOLES_DELTA_TO_MOVE);
+	}
+	match gas.max_difference(other) {
+		(Some(idx), delta) => {
+			delta
+				>= thresholds
+					.get(idx)
+					.copied()
+					.unwrap_or(MINIMUM_MOLES_DELTA_TO_MOVE)
+		}
+		(None, _) => gas.compare_with(other, MINIMUM_MOLES_DELTA_TO_MOVE),
+	}
 }
 
 // Compares with neighbors, returning early if any of them are valid.
@@ -316,36 +864,63 @@ fn should_process(
 	mixture: &TurfMixture,
 	all_mixtures: &[RwLock<Mixture>],
 	arena: &TurfGases,
+	thresholds: &[f32],
 ) -> bool {
 	mixture.enabled()
 		&& arena.adjacent_node_ids(index).next().is_some()
-		&& all_mixtures
-			.get(mixture.mix)
-			.and_then(RwLock::try_read)
-			.map_or(false, |gas| {
-				for entry in arena.adjacent_mixes(index, all_mixtures) {
-					if let Some(mix) = entry.try_read() {
-						if gas.temperature_compare(&mix)
-							|| gas.compare_with(&mix, MINIMUM_MOLES_DELTA_TO_MOVE)
-						{
-							return true;
+		&& (KEPT_ACTIVE_TURFS.contains_key(&mixture.id)
+			|| all_mixtures
+				.get(mixture.mix)
+				.and_then(RwLock::try_read)
+				.map_or(false, |gas| {
+					for entry in arena.adjacent_mixes(index, all_mixtures) {
+						if let Some(mix) = entry.try_read() {
+							if differs_enough(&gas, &mix, thresholds) {
+								return true;
+							}
+						} else {
+							return false;
 						}
-					} else {
-						return false;
 					}
-				}
-				false
-			})
+					false
+				}))
+}
+
+// Which turfs a share is closed over. Turfs only share with neighbors in the same scope,
+// so however many scopes a step is split into, none of them makes or loses gas at its edges.
+enum SharingScope<'a> {
+	All,
+	Turfs(&'a [TurfID]),
+	// A range of indices into the turf map, as shared by a sharing run with a budget.
+	Chunk(Range<usize>),
+}
+
+impl SharingScope<'_> {
+	fn includes(&self, id: TurfID, arena: &TurfGases) -> bool {
+		match self {
+			Self::All => true,
+			Self::Turfs(ids) => ids.contains(&id),
+			Self::Chunk(range) => arena
+				.map
+				.get_index_of(&id)
+				.map_or(false, |index| range.contains(&index)),
+		}
+	}
 }
 
 // Creates the combined gas mixture of all this mix's neighbors, as well as gathering some other pertinent info for future processing.
+// Also returns how much of this mix the neighbors take, as a ratio.
 // Clippy go away, this type is only used once
+// Only neighbors in `scope` are shared with.
 #[allow(clippy::type_complexity)]
 fn process_cell(
 	index: NodeIndex,
 	all_mixtures: &[RwLock<Mixture>],
 	arena: &TurfGases,
-) -> Option<(NodeIndex, Mixture, TinyVec<[(TurfID, f32); 6]>, i32)> {
+	diagonal_sharing: Option<DiagonalSharing>,
+	scope: &SharingScope,
+	floors: SimFloors,
+) -> Option<(NodeIndex, Mixture, TinyVec<[(TurfID, f32); 6]>, f32)> {
 	let mut adj_amount = 0;
 	/*
 		Getting write locks is potential danger zone,
@@ -364,12 +939,13 @@ fn process_cell(
 		due to the pressure gradient.
 		Technically that's ρν², but, like, video games.
 	*/
-	for (&loc, entry) in
-		arena.adjacent_mixes_with_adj_ids(index, all_mixtures, petgraph::Direction::Incoming)
+	for (&loc, entry) in arena
+		.adjacent_mixes_with_adj_ids(index, all_mixtures, petgraph::Direction::Incoming)
+		.filter(|&(&loc, _)| scope.includes(loc, arena))
 	{
 		match entry.try_read() {
 			Some(mix) => {
-				end_gas.merge(&mix);
+				end_gas.merge_with_floors(&mix, floors);
 				adj_amount += 1;
 				pressure_diffs.push((loc, -mix.return_pressure() * GAS_DIFFUSION_CONSTANT));
 			}
@@ -392,12 +968,198 @@ fn process_cell(
 		(Technically up to 2,097,152,
 		but I digress.)
 	*/
-	end_gas.multiply(GAS_DIFFUSION_CONSTANT);
-	Some((index, end_gas, pressure_diffs, adj_amount))
+	end_gas.multiply_with_floors(GAS_DIFFUSION_CONSTANT, floors);
+	let mut taken = adj_amount as f32 * GAS_DIFFUSION_CONSTANT;
+	if let Some(diagonals) = diagonal_sharing {
+		let mut diagonal_gas = Mixture::from_vol(crate::constants::CELL_VOLUME);
+		let mut diagonal_amount = 0;
+		for entry in arena
+			.diagonal_node_ids(index, diagonals.max_x, diagonals.max_y)
+			.filter_map(|diagonal| arena.get(diagonal))
+			.filter(|diagonal| scope.includes(diagonal.id, arena))
+			.filter_map(|diagonal| all_mixtures.get(diagonal.mix))
+		{
+			match entry.try_read() {
+				Some(mix) => {
+					diagonal_gas.merge_with_floors(&mix, floors);
+					diagonal_amount += 1;
+				}
+				None => return None,
+			}
+		}
+		diagonal_gas.multiply_with_floors(diagonals.coefficient, floors);
+		end_gas.merge_with_floors(&diagonal_gas, floors);
+		taken += diagonal_amount as f32 * diagonals.coefficient;
+	}
+	Some((index, end_gas, pressure_diffs, taken))
+}
+
+// Takes the combined gas of the cell's neighbors from process_cell and actually shares it with the cell, returning the pressure differences.
+fn share_cell(
+	i: NodeIndex,
+	end_gas: &Mixture,
+	mut pressure_diffs: TinyVec<[(TurfID, f32); 6]>,
+	taken: f32,
+	all_mixtures: &[RwLock<Mixture>],
+	arena: &TurfGases,
+	floors: SimFloors,
+) -> Option<(NodeIndex, TinyVec<[(TurfID, f32); 6]>, f32)> {
+	let m = arena.get(i).unwrap();
+	all_mixtures.get(m.mix).map(|entry| {
+		let mut max_diff = 0.0_f32;
+		let moved_pressure = {
+			let gas = entry.read();
+			gas.return_pressure() * GAS_DIFFUSION_CONSTANT
+		};
+		for pressure_diff in &mut pressure_diffs {
+			// pressure_diff.1 here was set to a negative above, so we just add.
+			pressure_diff.1 += moved_pressure;
+			max_diff = max_diff.max(pressure_diff.1.abs());
+		}
+		/*
+			1.0 - taken, that is 1.0 - GAS_DIFFUSION_CONSTANT * adj_amount
+			plus whatever the diagonals took, is going to be
+			precisely equal to the amount the surrounding tiles'
+			end_gas have "taken" from this tile--
+			they didn't actually take anything, just calculated
+			how much would be. This is the "taking" step.
+			Just to illustrate: say you have a turf with 3 neighbors.
+			Each of those neighbors will have their end_gas added to by
+			GAS_DIFFUSION_CONSTANT (at this writing, 0.125) times
+			this gas. So, 1.0 - (0.125 * adj_amount) = 0.625--
+			exactly the amount those gases "took" from this.
+		*/
+		{
+			let gas: &mut Mixture = &mut entry.write();
+			gas.multiply_with_floors(1.0 - taken, floors);
+			gas.merge_with_floors(end_gas, floors);
+			gas.record_history();
+		}
+		/*
+			If there is neither a major pressure difference
+			nor are there any visible gases nor does it need
+			to react, we're done outright. We don't need
+			to do any more and we don't need to send the
+			value to byond, so we don't. However, if we do...
+		*/
+		(i, pressure_diffs, max_diff)
+	})
+}
+
+// Every turf a share touches: the ones sharing, and every neighbor they take gas from.
+fn sharing_participants(
+	turfs_to_save: &[(NodeIndex, Mixture, TinyVec<[(TurfID, f32); 6]>, f32)],
+	arena: &TurfGases,
+	diagonal_sharing: Option<DiagonalSharing>,
+) -> Vec<NodeIndex> {
+	let mut participants = HashSet::new();
+	for &(index, _, _, _) in turfs_to_save {
+		participants.insert(index);
+		participants.extend(arena.adjacent_node_ids(index));
+		if let Some(diagonals) = diagonal_sharing {
+			participants.extend(arena.diagonal_node_ids(index, diagonals.max_x, diagonals.max_y));
+		}
+	}
+	participants.into_iter().collect()
+}
+
+// The total moles and thermal energy in the given turfs' air, summed in f64.
+fn conservation_totals(
+	participants: &[NodeIndex],
+	all_mixtures: &[RwLock<Mixture>],
+	arena: &TurfGases,
+) -> (f64, f64) {
+	participants
+		.par_iter()
+		.filter_map(|&index| all_mixtures.get(arena.get(index)?.mix))
+		.map(|mix| {
+			let mix = mix.read();
+			(
+				f64::from(mix.total_moles()),
+				f64::from(mix.thermal_energy()),
+			)
+		})
+		.reduce(
+			|| (0.0, 0.0),
+			|(moles_a, energy_a), (moles_b, energy_b)| (moles_a + moles_b, energy_a + energy_b),
+		)
+}
+
+// Reports a runtime if the totals drifted more than CONSERVATION_TOLERANCE over a share.
+// Byond can change turf air while sharing runs, so the odd report can be a false alarm; steady ones are real.
+fn check_conservation(before: (f64, f64), after: (f64, f64), turfs: usize) {
+	for (what, before, after) in [("moles", before.0, after.0), ("energy", before.1, after.1)] {
+		if (after - before).abs() > before.abs() * CONSERVATION_TOLERANCE {
+			let message = format!(
+				"Turf sharing didn't conserve {}: {} before, {} after, over {} turfs",
+				what, before, after, turfs
+			);
+			drop(byond_callback_sender().try_send(Box::new(move || Err(runtime!("{}", message)))));
+		}
+	}
+}
+
+/// Args: (enabled). Turns checking that turf sharing conserves moles and energy on or off, off by default.
+/// Each share sums the moles and energy of every turf involved before and after, and reports a runtime if they drift.
+/// Slow; for tracking down where gas is going missing.
+#[hook("/proc/auxmos_enable_conservation_checks")]
+fn _hook_enable_conservation_checks(enabled: Value) {
+	CONSERVATION_CHECKS.store(
+		enabled.as_number().map_or(false, |n| n != 0.0),
+		Ordering::Relaxed,
+	);
+	Ok(Value::null())
+}
+
+// Tells byond about the pressure differences between the turf and its neighbors, so it can throw things around. Main thread only.
+fn consider_pressure_differences(id: TurfID, diffs: &[(TurfID, f32)]) -> Result<(), Runtime> {
+	let turf = unsafe { Value::turf_by_id_unchecked(id) };
+	for (id, diff) in diffs.iter().copied() {
+		if id != 0 {
+			let enemy_tile = unsafe { Value::turf_by_id_unchecked(id) };
+			if diff > 5.0 {
+				turf.call(
+					"consider_pressure_difference",
+					&[&enemy_tile, &Value::from(diff)],
+				)?;
+			} else if diff < -5.0 {
+				enemy_tile.call(
+					"consider_pressure_difference",
+					&[&turf.clone(), &Value::from(-diff)],
+				)?;
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Shares gas between turfs like fdm, but within a budget of max_ms, 0 meaning no limit.
+/// With a budget, turfs are shared a chunk at a time from where the last run left off, and no new chunk is started
+/// once the budget's spent. The next run picks up from there, so turfs that got cut off go first.
+pub fn process_turfs_with_deadline(
+	max_ms: u64,
+	fdm_max_steps: i32,
+	equalize_enabled: bool,
+	diagonal_sharing: Option<DiagonalSharing>,
+) -> (BTreeSet<NodeIndex>, BTreeSet<NodeIndex>) {
+	let deadline = (max_ms > 0).then(|| Instant::now() + Duration::from_millis(max_ms));
+	fdm(fdm_max_steps, equalize_enabled, diagonal_sharing, deadline)
+}
+
+#[shutdown]
+fn _shutdown_turf_cursor() {
+	// a run that's still going would store its cursor after we reset it
+	wait_for_tasks();
+	TURF_CURSOR.store(0, Ordering::Relaxed);
 }
 
 // Solving the heat equation using a Finite Difference Method, an iterative stencil loop.
-fn fdm(fdm_max_steps: i32, equalize_enabled: bool) -> (BTreeSet<NodeIndex>, BTreeSet<NodeIndex>) {
+fn fdm(
+	fdm_max_steps: i32,
+	equalize_enabled: bool,
+	diagonal_sharing: Option<DiagonalSharing>,
+	deadline: Option<Instant>,
+) -> (BTreeSet<NodeIndex>, BTreeSet<NodeIndex>) {
 	/*
 		This is the replacement system for LINDA. LINDA requires a lot of bookkeeping,
 		which, when coefficient-wise operations are this fast, is all just unnecessary overhead.
@@ -407,17 +1169,19 @@ fn fdm(fdm_max_steps: i32, equalize_enabled: bool) -> (BTreeSet<NodeIndex>, BTre
 	let mut low_pressure_turfs: BTreeSet<NodeIndex> = Default::default();
 	let mut high_pressure_turfs: BTreeSet<NodeIndex> = Default::default();
 	let mut cur_count = 1;
+	let pressure_threshold = equalize_tuning().pressure_threshold;
+	let track_excited = EXCITED_CALLBACK.read().is_some();
+	let track_active = TRACK_ACTIVE_TURFS.load(Ordering::Relaxed);
+	let thresholds = GAS_PROCESS_THRESHOLDS.read().clone();
+	let floors = sim_floors();
 	with_turf_gases_read(|arena| {
-		loop {
-			if cur_count > fdm_max_steps || check_turfs_dirty() {
-				break;
-			}
+		// Shares the turfs at the given indices of the turf map, only with neighbors in scope.
+		let mut share_turfs = |turfs: Range<usize>, scope: SharingScope| {
 			GasArena::with_all_mixtures(|all_mixtures| {
-				let turfs_to_save = arena
-					.map
+				let turfs_to_save = turfs
 					/*
-						This directly yanks the internal node vec
-						of the graph as a slice to parallelize the process.
+						This indexes straight into the turf map
+						to parallelize the process.
 						The speedup gained from this is actually linear
 						with the amount of cores the CPU has, which, to be frank,
 						is way better than I was expecting, even though this operation
@@ -425,10 +1189,23 @@ fn fdm(fdm_max_steps: i32, equalize_enabled: bool) -> (BTreeSet<NodeIndex>, BTre
 						some maximum due to the global turf mixture lock access,
 						but it's already blazingly fast on my i7, so it should be fine.
 					*/
-					.par_values()
-					.map(|&idx| (idx, arena.get(idx).unwrap()))
-					.filter(|(index, mixture)| should_process(*index, mixture, all_mixtures, arena))
-					.filter_map(|(index, _)| process_cell(index, all_mixtures, arena))
+					.into_par_iter()
+					.filter_map(|i| arena.map.get_index(i))
+					.map(|(_, &idx)| (idx, arena.get(idx).unwrap()))
+					.filter(|(index, mixture)| {
+						let processing =
+							should_process(*index, mixture, all_mixtures, arena, &thresholds);
+						if track_excited {
+							track_excitement(mixture.id, processing);
+						}
+						if track_active && processing {
+							PASS_ACTIVE_TURFS.insert(mixture.id);
+						}
+						processing
+					})
+					.filter_map(|(index, _)| {
+						process_cell(index, all_mixtures, arena, diagonal_sharing, &scope, floors)
+					})
 					.collect::<Vec<_>>();
 				/*
 					For the optimization-heads reading this: this is not an unnecessary collect().
@@ -438,49 +1215,33 @@ fn fdm(fdm_max_steps: i32, equalize_enabled: bool) -> (BTreeSet<NodeIndex>, BTre
 					In short: the above actually needs to finish before the below starts
 					for consistency, so collect() is desired. This has been tested, by the way.
 				*/
+				let participants = CONSERVATION_CHECKS
+					.load(Ordering::Relaxed)
+					.then(|| sharing_participants(&turfs_to_save, arena, diagonal_sharing));
+				let totals_before = participants
+					.as_ref()
+					.map(|participants| conservation_totals(participants, all_mixtures, arena));
 				let (low_pressure, high_pressure): (Vec<_>, Vec<_>) = turfs_to_save
 					.into_par_iter()
-					.filter_map(|(i, end_gas, mut pressure_diffs, adj_amount)| {
-						let m = arena.get(i).unwrap();
-						all_mixtures.get(m.mix).map(|entry| {
-							let mut max_diff = 0.0_f32;
-							let moved_pressure = {
-								let gas = entry.read();
-								gas.return_pressure() * GAS_DIFFUSION_CONSTANT
-							};
-							for pressure_diff in &mut pressure_diffs {
-								// pressure_diff.1 here was set to a negative above, so we just add.
-								pressure_diff.1 += moved_pressure;
-								max_diff = max_diff.max(pressure_diff.1.abs());
-							}
-							/*
-								1.0 - GAS_DIFFUSION_CONSTANT * adj_amount is going to be
-								precisely equal to the amount the surrounding tiles'
-								end_gas have "taken" from this tile--
-								they didn't actually take anything, just calculated
-								how much would be. This is the "taking" step.
-								Just to illustrate: say you have a turf with 3 neighbors.
-								Each of those neighbors will have their end_gas added to by
-								GAS_DIFFUSION_CONSTANT (at this writing, 0.125) times
-								this gas. So, 1.0 - (0.125 * adj_amount) = 0.625--
-								exactly the amount those gases "took" from this.
-							*/
-							{
-								let gas: &mut Mixture = &mut entry.write();
-								gas.multiply(1.0 - (adj_amount as f32 * GAS_DIFFUSION_CONSTANT));
-								gas.merge(&end_gas);
-							}
-							/*
-								If there is neither a major pressure difference
-								nor are there any visible gases nor does it need
-								to react, we're done outright. We don't need
-								to do any more and we don't need to send the
-								value to byond, so we don't. However, if we do...
-							*/
-							(i, pressure_diffs, max_diff)
-						})
+					.filter_map(|(i, end_gas, pressure_diffs, taken)| {
+						share_cell(
+							i,
+							&end_gas,
+							pressure_diffs,
+							taken,
+							all_mixtures,
+							arena,
+							floors,
+						)
 					})
-					.partition(|&(_, _, max_diff)| max_diff <= 5.0);
+					.partition(|&(_, _, max_diff)| max_diff <= pressure_threshold);
+				if let (Some(participants), Some(before)) = (participants, totals_before) {
+					check_conservation(
+						before,
+						conservation_totals(&participants, all_mixtures, arena),
+						participants.len(),
+					);
+				}
 
 				high_pressure_turfs.par_extend(high_pressure.par_iter().map(|(i, _, _)| i));
 				low_pressure_turfs.par_extend(low_pressure.par_iter().map(|(i, _, _)| i));
@@ -494,37 +1255,52 @@ fn fdm(fdm_max_steps: i32, equalize_enabled: bool) -> (BTreeSet<NodeIndex>, BTre
 						.for_each(|(id, diffs)| {
 							let sender = byond_callback_sender();
 							drop(sender.try_send(Box::new(move || {
-								let turf = unsafe { Value::turf_by_id_unchecked(id) };
-								for (id, diff) in diffs.iter().copied() {
-									if id != 0 {
-										let enemy_tile = unsafe { Value::turf_by_id_unchecked(id) };
-										if diff > 5.0 {
-											turf.call(
-												"consider_pressure_difference",
-												&[&enemy_tile, &Value::from(diff)],
-											)?;
-										} else if diff < -5.0 {
-											enemy_tile.call(
-												"consider_pressure_difference",
-												&[&turf.clone(), &Value::from(-diff)],
-											)?;
-										}
-									}
-								}
-								Ok(())
+								consider_pressure_differences(id, &diffs)
 							})));
 						});
 				}
 			});
+		};
+		let turf_count = arena.map.len();
+		let mut cursor = TURF_CURSOR.load(Ordering::Relaxed) % turf_count.max(1);
+		'steps: loop {
+			if cur_count > fdm_max_steps || check_turfs_dirty() {
+				break;
+			}
+			match deadline {
+				None => share_turfs(0..turf_count, SharingScope::All),
+				Some(deadline) => {
+					// Each chunk only shares within itself, so gas only crosses its edges once they've moved.
+					let mut shared = 0;
+					while shared < turf_count {
+						// always one chunk a run, or a budget that's too tight would never get anything shared
+						if (cur_count > 1 || shared > 0) && Instant::now() >= deadline {
+							break 'steps;
+						}
+						let end =
+							(cursor + DEADLINE_CHUNK_SIZE.min(turf_count - shared)).min(turf_count);
+						share_turfs(cursor..end, SharingScope::Chunk(cursor..end));
+						shared += end - cursor;
+						cursor = if end == turf_count { 0 } else { end };
+					}
+					// start the next step half a chunk over, so its chunks straddle this one's edges
+					cursor = (cursor + DEADLINE_CHUNK_SIZE / 2) % turf_count.max(1);
+				}
+			}
 			cur_count += 1;
 		}
+		TURF_CURSOR.store(cursor, Ordering::Relaxed);
 	});
 	(low_pressure_turfs, high_pressure_turfs)
 }
 
-// Finds small differences in turf pressures and equalizes them.
-fn excited_group_processing(pressure_goal: f32, low_pressure_turfs: &BTreeSet<NodeIndex>) -> usize {
+// Finds small differences in turf pressures and equalizes them, returning the turfs in each group formed.
+fn excited_group_processing(
+	pressure_goal: f32,
+	low_pressure_turfs: &BTreeSet<NodeIndex>,
+) -> (usize, Vec<Vec<TurfID>>) {
 	let mut found_turfs: HashSet<NodeIndex, FxBuildHasher> = Default::default();
+	let mut groups: Vec<Vec<TurfID>> = Vec::new();
 	with_turf_gases_read(|arena| {
 		for &initial_turf in low_pressure_turfs.iter() {
 			if found_turfs.contains(&initial_turf) {
@@ -535,7 +1311,6 @@ fn excited_group_processing(pressure_goal: f32, low_pressure_turfs: &BTreeSet<No
 			let mut turfs: Vec<&TurfMixture> = Vec::with_capacity(200);
 			let mut min_pressure = initial_mix_ref.return_pressure();
 			let mut max_pressure = min_pressure;
-			let mut fully_mixed = Mixture::new();
 			border_turfs.push_back(initial_turf);
 			found_turfs.insert(initial_turf);
 			GasArena::with_all_mixtures(|all_mixtures| {
@@ -556,8 +1331,6 @@ fn excited_group_processing(pressure_goal: f32, low_pressure_turfs: &BTreeSet<No
 							min_pressure = this_min;
 							max_pressure = this_max;
 							turfs.push(tmix);
-							fully_mixed.merge(&mix);
-							fully_mixed.volume += mix.volume;
 							for loc in arena.adjacent_node_ids(idx) {
 								if found_turfs.contains(&loc) {
 									continue;
@@ -572,18 +1345,61 @@ fn excited_group_processing(pressure_goal: f32, low_pressure_turfs: &BTreeSet<No
 						break;
 					}
 				}
-				fully_mixed.multiply(1.0 / turfs.len() as f32);
-				if !fully_mixed.is_corrupt() {
-					turfs.par_iter().with_min_len(125).for_each(|turf| {
-						if let Some(mix_lock) = all_mixtures.get(turf.mix) {
-							mix_lock.write().copy_from_mutable(&fully_mixed);
-						}
-					});
+				let mix_ids = turfs.iter().map(|turf| turf.mix).collect::<Vec<_>>();
+				if average_group(&mix_ids, all_mixtures) && turfs.len() > 1 {
+					groups.push(turfs.iter().map(|turf| turf.id).collect());
 				}
 			});
 		}
 	});
-	found_turfs.len()
+	(found_turfs.len(), groups)
+}
+
+/// Sets every mix in the group to the group's average, so each ends up with an equal share of the group's moles,
+/// empty ones included, at the temperature of the group's combined thermal energy.
+/// Sums are done in f64 so repeatedly averaging a room doesn't slowly create or destroy gas.
+/// Returns false without changing anything if the result would be corrupt.
+pub fn average_group(ids: &[usize], all_mixtures: &[RwLock<Mixture>]) -> bool {
+	if ids.is_empty() {
+		return false;
+	}
+	let mut moles: Vec<f64> = vec![0.0; crate::gas::total_num_gases()];
+	let mut heat_capacity = 0.0_f64;
+	let mut thermal_energy = 0.0_f64;
+	for mix in ids.iter().filter_map(|&id| all_mixtures.get(id)) {
+		let mix = mix.read();
+		for (idx, amt) in mix.enumerate() {
+			if let Some(total) = moles.get_mut(idx) {
+				*total += f64::from(amt);
+			}
+		}
+		let cap = f64::from(mix.heat_capacity());
+		heat_capacity += cap;
+		thermal_energy += cap * f64::from(mix.get_temperature());
+	}
+	let count = ids.len() as f64;
+	let mut averaged = Mixture::new();
+	averaged.set_moles_bulk(
+		&moles
+			.iter()
+			.enumerate()
+			.map(|(idx, &total)| (idx, (total / count) as f32))
+			.collect::<Vec<_>>(),
+	);
+	if heat_capacity > f64::from(MINIMUM_HEAT_CAPACITY) {
+		averaged.set_temperature((thermal_energy / heat_capacity) as f32);
+	} else if let Some(first) = all_mixtures.get(ids[0]) {
+		averaged.set_temperature(first.read().get_temperature());
+	}
+	if averaged.is_corrupt() {
+		return false;
+	}
+	ids.par_iter().with_min_len(125).for_each(|&id| {
+		if let Some(mix_lock) = all_mixtures.get(id) {
+			mix_lock.write().copy_from_mutable(&averaged);
+		}
+	});
+	true
 }
 
 // Checks if the gas can react or can update visuals, returns None if not.
@@ -591,7 +1407,7 @@ fn post_process_cell<'a>(
 	mixture: &'a TurfMixture,
 	vis: &[Option<f32>],
 	all_mixtures: &[RwLock<Mixture>],
-	reactions: &BTreeMap<crate::reaction::ReactionPriority, crate::reaction::Reaction>,
+	reactions: &BTreeMap<crate::reaction::ReactionOrder, crate::reaction::Reaction>,
 ) -> Option<(&'a TurfMixture, bool, bool)> {
 	all_mixtures
 		.get(mixture.mix)
@@ -607,10 +1423,31 @@ fn post_process_cell<'a>(
 		})
 }
 
+// Clears trace gases out of a turf that wasn't shared this run and normalizes it, so idle turfs keep the same content hash.
+// Skipped if something else has its mix locked. Only takes the write lock if there's something to change.
+fn gc_idle_turf(mixture: &TurfMixture, all_mixtures: &[RwLock<Mixture>], floors: SimFloors) {
+	if let Some(gas_read) = all_mixtures
+		.get(mixture.mix)
+		.and_then(RwLock::try_upgradable_read)
+	{
+		if gas_read.needs_normalizing(floors) {
+			if let Ok(mut gas) =
+				parking_lot::lock_api::RwLockUpgradableReadGuard::try_upgrade(gas_read)
+			{
+				gas.normalize_with_floors(floors.min_moles, floors);
+			}
+		}
+	}
+}
+
 // Goes through every turf, checks if it should reset to planet atmos, if it should
 // update visuals, if it should react, sends a callback if it should.
-fn post_process() {
+fn post_process(
+	low_pressure_turfs: &BTreeSet<NodeIndex>,
+	high_pressure_turfs: &BTreeSet<NodeIndex>,
+) {
 	let vis = crate::gas::visibility_copies();
+	let floors = sim_floors();
 	with_turf_gases_read(|arena| {
 		let processables = crate::gas::types::with_reactions(|reactions| {
 			GasArena::with_all_mixtures(|all_mixtures| {
@@ -619,6 +1456,11 @@ fn post_process() {
 					.par_values()
 					.filter_map(|&node_index| {
 						let mix = arena.get(node_index).unwrap();
+						if !low_pressure_turfs.contains(&node_index)
+							&& !high_pressure_turfs.contains(&node_index)
+						{
+							gc_idle_turf(mix, all_mixtures, floors);
+						}
 						mix.enabled().then_some(mix)
 					})
 					.filter_map(|mixture| post_process_cell(mixture, &vis, all_mixtures, reactions))
@@ -4,7 +4,7 @@ use super::*;
 
 //use indexmap::IndexSet;
 
-use crate::GasArena;
+use crate::{gas::WorkerPhase, GasArena};
 
 use auxcallback::byond_callback_sender;
 
@@ -17,8 +17,10 @@ static INIT_HEAT: Once = Once::new();
 static TURF_HEAT: RwLock<Option<TurfHeat>> = const_rwlock(None);
 
 lazy_static::lazy_static! {
-	static ref HEAT_CHANNEL: (flume::Sender<SSheatInfo>, flume::Receiver<SSheatInfo>) =
-		flume::bounded(1);
+	static ref HEAT_CHANNEL: (
+		flume::Sender<(SSheatInfo, WorkerPhase)>,
+		flume::Receiver<(SSheatInfo, WorkerPhase)>
+	) = flume::bounded(1);
 }
 
 #[init(partial)]
@@ -66,11 +68,13 @@ struct ThermalInfo {
 	pub temperature: RwLock<f32>,
 }
 
-fn with_heat_processing_callback_receiver<T>(f: impl Fn(&flume::Receiver<SSheatInfo>) -> T) -> T {
+fn with_heat_processing_callback_receiver<T>(
+	f: impl Fn(&flume::Receiver<(SSheatInfo, WorkerPhase)>) -> T,
+) -> T {
 	f(&HEAT_CHANNEL.1)
 }
 
-fn heat_processing_callbacks_sender() -> flume::Sender<SSheatInfo> {
+fn heat_processing_callbacks_sender() -> flume::Sender<(SSheatInfo, WorkerPhase)> {
 	HEAT_CHANNEL.0.clone()
 }
 type HeatGraphMap = IndexMap<TurfID, NodeIndex<usize>, FxBuildHasher>;
@@ -178,7 +182,7 @@ pub fn supercond_update_ref(src: Value) -> Result<(), Runtime> {
 			thermal_conductivity: therm_cond,
 			temperature: RwLock::new(
 				src.get_number(byond_string!("initial_temperature"))
-					.unwrap_or(TCMB),
+					.unwrap_or(crate::gas::mixture::sim_floors().min_temperature),
 			),
 		};
 		with_turf_heat_write(|arena| arena.insert_turf(therm_info));
@@ -261,6 +265,9 @@ fn _process_heat_notify() {
 		turf tiles are 1 meter^2 anyway--the atmos subsystem
 		does this in general, thus turf gas mixtures being 2.5 m^3.
 	*/
+	if atmos_paused() {
+		return Ok(Value::null());
+	}
 	let sender = heat_processing_callbacks_sender();
 	let time_delta = (src.get_number(byond_string!("wait")).map_err(|_| {
 		runtime!(
@@ -270,7 +277,7 @@ fn _process_heat_notify() {
 			std::column!()
 		)
 	})? / 10.0) as f64;
-	_ = sender.try_send(SSheatInfo { time_delta });
+	_ = sender.try_send((SSheatInfo { time_delta }, WorkerPhase::begin()));
 	Ok(Value::null())
 }
 
@@ -284,12 +291,14 @@ fn _process_heat_start() -> Result<(), String> {
 	INIT_HEAT.call_once(|| {
 		rayon::spawn(|| loop {
 			//this will block until process_turf_heat is called
-			let info = with_heat_processing_callback_receiver(|receiver| receiver.recv().unwrap());
+			let (info, phase) =
+				with_heat_processing_callback_receiver(|receiver| receiver.recv().unwrap());
 			let task_lock = TASKS.read();
 			let start_time = Instant::now();
 			let sender = byond_callback_sender();
 			let _emissivity_constant: f64 = STEFAN_BOLTZMANN_CONSTANT * info.time_delta;
 			let _radiation_from_space_tick: f64 = RADIATION_FROM_SPACE * info.time_delta;
+			let min_temperature = crate::gas::mixture::sim_floors().min_temperature;
 			with_turf_heat_read(|arena| {
 				with_turf_gases_read(|air_arena| {
 					let adjacencies_to_consider = arena
@@ -368,7 +377,7 @@ fn _process_heat_start() -> Result<(), String> {
 							*/
 							//share w/ space
 							if info.adjacent_to_space && *temp_write > T20C {
-								let delta = *temp_write - TCMB;
+								let delta = *temp_write - min_temperature;
 								let energy = get_share_energy(
 									info.thermal_conductivity * delta,
 									HEAT_CAPACITY_VACUUM,
@@ -403,7 +412,7 @@ fn _process_heat_start() -> Result<(), String> {
 							}
 
 							if !temp_write.is_normal() {
-								*temp_write = TCMB;
+								*temp_write = min_temperature;
 							}
 
 							if *temp_write > MINIMUM_TEMPERATURE_START_SUPERCONDUCTION
@@ -476,6 +485,7 @@ fn _process_heat_start() -> Result<(), String> {
 				Ok(())
 			})));
 			drop(task_lock);
+			drop(phase);
 		});
 	});
 	Ok(())